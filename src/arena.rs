@@ -1,9 +1,119 @@
 use bevy::prelude::*;
 
+use crate::primitives::{Rec, Scaling};
+use crate::settings::{ArenaPattern, Settings};
+use crate::theme::{ActiveTheme, ThemeColor};
+
 pub const ARENA_WIDTH: f32 = 21.0;
 pub const ARENA_HEIGHT: f32 = ARENA_WIDTH;
 pub const ARENA_BUFFER: f32 = 4.0;
 pub const ARENA_COLOR: Color = Color::rgb(0.7, 0.7, 0.7);
+pub const GRID_COLOR: Color = Color::rgb(0.62, 0.62, 0.62);
+
+/// Width of grid lines as a fraction of a cell. Lines never get thinner than a
+/// pixel.
+const GRID_LINE: f32 = 0.06;
+
+pub struct ArenaPlugin;
+
+impl Plugin for ArenaPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(draw_arena_pattern);
+    }
+}
 
 #[derive(Component)]
 pub struct Arena;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArenaSize {
+    pub width: i32,
+    pub height: i32,
+}
+
+impl Default for ArenaSize {
+    fn default() -> Self {
+        ArenaSize {
+            width: ARENA_WIDTH as i32,
+            height: ARENA_HEIGHT as i32,
+        }
+    }
+}
+
+#[derive(Component)]
+struct PatternTile;
+
+/// Redraws the grid or checkerboard as children of every arena. Tiles are laid
+/// out in the arena's local space, where the whole arena is one unit across.
+fn draw_arena_pattern(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    theme: Res<ActiveTheme>,
+    scaling: Res<Scaling>,
+    arenas: Query<(Entity, &Rec, Option<&Children>), With<Arena>>,
+    resized: Query<(), (With<Arena>, Changed<Rec>)>,
+    tiles: Query<(), With<PatternTile>>,
+) {
+    for (entity, rec, children) in arenas.iter() {
+        if resized.get(entity).is_err() && !settings.is_changed() && !scaling.is_changed() {
+            continue;
+        }
+
+        for child in children.iter().flat_map(|children| children.iter()) {
+            if tiles.get(*child).is_ok() {
+                commands.entity(*child).despawn_recursive();
+            }
+        }
+
+        if scaling.0 <= 0.0 {
+            continue;
+        }
+
+        let width = rec.width as f32;
+        let height = rec.height as f32;
+        let tile = |x: f32, y: f32, w: f32, h: f32| SpriteBundle {
+            sprite: Sprite {
+                color: theme.palette.grid,
+                ..Default::default()
+            },
+            transform: Transform {
+                translation: Vec3::new(x / width, y / height, 0.1),
+                scale: Vec3::new(w / width, h / height, 1.0),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let mut bundles = Vec::new();
+        match settings.video.arena_pattern {
+            ArenaPattern::Plain => {}
+            ArenaPattern::Grid => {
+                let line = GRID_LINE.max(1.0 / scaling.0);
+                for x in 1..rec.width {
+                    bundles.push(tile(x as f32 - width / 2.0, 0.0, line, height));
+                }
+                for y in 1..rec.height {
+                    bundles.push(tile(0.0, y as f32 - height / 2.0, width, line));
+                }
+            }
+            ArenaPattern::Checkerboard => {
+                for x in -rec.width / 2..=rec.width / 2 {
+                    for y in -rec.height / 2..=rec.height / 2 {
+                        if (x + y) % 2 == 0 {
+                            bundles.push(tile(x as f32, y as f32, 1.0, 1.0));
+                        }
+                    }
+                }
+            }
+        }
+
+        commands.entity(entity).with_children(|parent| {
+            for bundle in bundles {
+                parent
+                    .spawn_bundle(bundle)
+                    .insert(PatternTile)
+                    .insert(ThemeColor::Grid);
+            }
+        });
+    }
+}
@@ -0,0 +1,123 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+
+use crate::arena::ArenaSize;
+use crate::game_loop::{GameStage, SnakeHead, TickEvent};
+use crate::game_state::GameState;
+use crate::rules::{self, Direction, Position};
+use crate::settings::Settings;
+use crate::sound::{Sound, SoundOutput};
+
+/// Cells ahead of the head checked for a wall or the tail.
+const DANGER_RANGE: i32 = 3;
+/// Semitones the danger cue rises as the hazard gets a cell closer.
+const DANGER_PITCH_STEP: f32 = 4.0;
+/// Semitones the food cue rises per row the food is above the head.
+const FOOD_PITCH_STEP: f32 = 1.0;
+const MAX_FOOD_SEMITONES: f32 = 12.0;
+/// Quietest the food cue gets, however far away the food is.
+const MIN_FOOD_VOLUME: f32 = 0.2;
+
+pub struct AudioCuesPlugin;
+
+impl Plugin for AudioCuesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(
+            SystemSet::on_update(GameState::MainGameLoop)
+                .with_system(play_audio_cues.after(GameStage::Collection)),
+        );
+    }
+}
+
+/// One tick's worth of sound describing what is around the head.
+struct Cue {
+    sound: Sound,
+    volume: f32,
+    /// `-1.0` is fully left, `1.0` fully right.
+    pan: f32,
+    pitch: f32,
+}
+
+fn semitones(steps: f32) -> f32 {
+    2.0_f32.powf(steps / 12.0)
+}
+
+/// Pans towards the nearest food, pitches up or down with its row and gets
+/// quieter the further away it is.
+fn food_cue(arena: ArenaSize, head: Position, food: &[Position]) -> Option<Cue> {
+    let nearest = food
+        .iter()
+        .filter(|food| **food != head)
+        .min_by_key(|food| (food.x - head.x).abs() + (food.y - head.y).abs())?;
+
+    let dx = nearest.x - head.x;
+    let dy = nearest.y - head.y;
+    let distance = (dx.abs() + dy.abs()) as f32;
+    let span = (arena.width + arena.height) as f32;
+
+    Some(Cue {
+        sound: Sound::FoodCue,
+        volume: (1.0 - distance / span).max(MIN_FOOD_VOLUME),
+        pan: (dx as f32 * 2.0 / arena.width as f32).clamp(-1.0, 1.0),
+        pitch: semitones(
+            (dy as f32 * FOOD_PITCH_STEP).clamp(-MAX_FOOD_SEMITONES, MAX_FOOD_SEMITONES),
+        ),
+    })
+}
+
+/// Warns about the first wall or tail segment within `DANGER_RANGE` cells of
+/// the head, louder and higher the closer it is.
+fn danger_cue(
+    arena: ArenaSize,
+    head: Position,
+    heading: Direction,
+    tail: &VecDeque<Position>,
+) -> Option<Cue> {
+    let mut position = head;
+
+    for distance in 1..=DANGER_RANGE {
+        position = rules::advance(position, heading);
+        if rules::hits_wall(arena, position) || tail.contains(&position) {
+            let closeness = (DANGER_RANGE - distance) as f32;
+            return Some(Cue {
+                sound: Sound::DangerCue,
+                volume: (closeness + 1.0) / DANGER_RANGE as f32,
+                pan: match heading {
+                    Direction::Left => -1.0,
+                    Direction::Right => 1.0,
+                    Direction::Up | Direction::Down => 0.0,
+                },
+                pitch: semitones(closeness * DANGER_PITCH_STEP),
+            });
+        }
+    }
+
+    None
+}
+
+fn play_audio_cues(
+    settings: Res<Settings>,
+    output: Res<SoundOutput>,
+    arena: Res<ArenaSize>,
+    mut events: EventReader<TickEvent>,
+    heads: Query<&SnakeHead>,
+) {
+    if !settings.audio.audio_cues {
+        return;
+    }
+
+    let no_tail = VecDeque::new();
+    for event in events.iter() {
+        let tail = heads
+            .get(event.snake)
+            .map_or(&no_tail, |head| &head.body.tail);
+
+        let cues = food_cue(*arena, event.position, &event.food)
+            .into_iter()
+            .chain(danger_cue(*arena, event.position, event.heading, tail));
+        for cue in cues {
+            output.play_cue(&settings, cue.sound, cue.volume, cue.pan, cue.pitch);
+        }
+    }
+}
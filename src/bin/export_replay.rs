@@ -0,0 +1,43 @@
+use std::process;
+
+use bevy_snake::export::{export_gif, export_png_sequence};
+use bevy_snake::replay::Replay;
+use bevy_snake::settings::Settings;
+use bevy_snake::theme::{Palette, Theme};
+
+const USAGE: &str = "usage: export_replay <replay.ron> <output.gif | output-directory> [--cell <pixels>] [--delay <centiseconds>]";
+
+fn main() {
+    if let Err(error) = run() {
+        eprintln!("{}", error);
+        process::exit(1);
+    }
+}
+
+fn run() -> Result<(), String> {
+    let mut args = std::env::args().skip(1);
+    let input = args.next().ok_or(USAGE)?;
+    let output = args.next().ok_or(USAGE)?;
+
+    let mut cell = 16;
+    let mut delay = 10;
+    while let Some(flag) = args.next() {
+        let value = args.next().ok_or(USAGE)?;
+        match flag.as_str() {
+            "--cell" => cell = value.parse().map_err(|_| USAGE)?,
+            "--delay" => delay = value.parse().map_err(|_| USAGE)?,
+            _ => return Err(USAGE.to_string()),
+        }
+    }
+
+    let replay = Replay::load(&input).map_err(|e| format!("{}: {}", input, e))?;
+    let settings = Settings::load().map_err(|e| e.to_string())?;
+    let theme = Theme::load(&settings.video.theme)?;
+    let palette = Palette::for_mode(settings.video.palette).unwrap_or(theme.palette);
+
+    if output.ends_with(".gif") {
+        export_gif(&replay, &palette, cell, delay, &output)
+    } else {
+        export_png_sequence(&replay, &palette, cell, &output)
+    }
+}
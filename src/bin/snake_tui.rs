@@ -0,0 +1,204 @@
+use std::io::{self, Stdout, Write};
+use std::process;
+use std::time::{Duration, Instant};
+
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers};
+use crossterm::style::Print;
+use crossterm::terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{execute, queue};
+
+use bevy_snake::rules::{hits_wall, DeathCause, Direction, Game, Position};
+use bevy_snake::settings::Settings;
+
+const USAGE: &str = "usage: snake_tui [--ascii] [--seed <number>]";
+
+struct Glyphs {
+    wall: &'static str,
+    empty: &'static str,
+    head: &'static str,
+    tail: &'static str,
+    food: &'static str,
+}
+
+// Every cell is two columns wide so the arena looks roughly square.
+const UNICODE: Glyphs = Glyphs {
+    wall: "░░",
+    empty: "  ",
+    head: "██",
+    tail: "▓▓",
+    food: "◆ ",
+};
+
+const ASCII: Glyphs = Glyphs {
+    wall: "##",
+    empty: "  ",
+    head: "@@",
+    tail: "oo",
+    food: "<>",
+};
+
+/// Puts the terminal into raw mode on an alternate screen and restores it
+/// when dropped, however the game ends.
+struct Terminal;
+
+impl Terminal {
+    fn enter() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        execute!(io::stdout(), EnterAlternateScreen, Hide)?;
+        Ok(Terminal)
+    }
+}
+
+impl Drop for Terminal {
+    fn drop(&mut self) {
+        let _ = execute!(io::stdout(), Show, LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+fn main() {
+    if let Err(error) = run() {
+        eprintln!("{}", error);
+        process::exit(1);
+    }
+}
+
+fn run() -> Result<(), String> {
+    let mut glyphs = &UNICODE;
+    let mut seed = None;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(flag) = args.next() {
+        match flag.as_str() {
+            "--ascii" => glyphs = &ASCII,
+            "--seed" => {
+                let value = args.next().ok_or(USAGE)?;
+                seed = Some(value.parse().map_err(|_| USAGE)?);
+            }
+            _ => return Err(USAGE.to_string()),
+        }
+    }
+
+    let settings = Settings::load().map_err(|e| e.to_string())?;
+    let _terminal = Terminal::enter().map_err(|e| e.to_string())?;
+    play(&settings, glyphs, seed).map_err(|e| e.to_string())
+}
+
+fn new_game(settings: &Settings, seed: Option<u64>) -> Game {
+    Game::new(
+        seed.unwrap_or_else(rand::random),
+        settings.gameplay.arena(),
+        settings.gameplay.food_rules(),
+    )
+}
+
+fn direction(code: KeyCode) -> Option<Direction> {
+    match code {
+        KeyCode::Up => Some(Direction::Up),
+        KeyCode::Down => Some(Direction::Down),
+        KeyCode::Left => Some(Direction::Left),
+        KeyCode::Right => Some(Direction::Right),
+        _ => None,
+    }
+}
+
+fn play(settings: &Settings, glyphs: &Glyphs, seed: Option<u64>) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    let tick = Duration::from_millis(settings.gameplay.tick_millis);
+    let mut game = new_game(settings, seed);
+    let mut paused = false;
+    let mut next_tick = Instant::now() + tick;
+
+    loop {
+        draw(&mut stdout, &game, glyphs, paused)?;
+
+        let timeout = next_tick.saturating_duration_since(Instant::now());
+        if event::poll(timeout)? {
+            if let Event::Key(KeyEvent { code, modifiers }) = event::read()? {
+                match code {
+                    KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => {
+                        return Ok(())
+                    }
+                    KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                    KeyCode::Char('p') if !game.is_over() => paused = !paused,
+                    KeyCode::Char('r') if game.is_over() => {
+                        game = new_game(settings, seed);
+                        next_tick = Instant::now() + tick;
+                    }
+                    code => {
+                        if let Some(direction) = direction(code).filter(|_| !paused) {
+                            game.steer(direction);
+                        }
+                    }
+                }
+            }
+            continue;
+        }
+
+        if !paused {
+            game.tick();
+        }
+        next_tick = Instant::now() + tick;
+    }
+}
+
+fn prompt(game: &Game, paused: bool) -> String {
+    match game.snake.cause {
+        Some(cause) => {
+            let cause = match cause {
+                DeathCause::Wall => "hit the wall",
+                DeathCause::Tail => "bit its own tail",
+            };
+            format!("Game over: {}. Press R to retry or Q to quit.", cause)
+        }
+        None if paused => "Paused. Press P to resume or Q to quit.".to_string(),
+        None => "Arrow keys to steer, P to pause, Q to quit.".to_string(),
+    }
+}
+
+fn draw(stdout: &mut Stdout, game: &Game, glyphs: &Glyphs, paused: bool) -> io::Result<()> {
+    let half_width = game.arena.width / 2 + 1;
+    let half_height = game.arena.height / 2 + 1;
+
+    queue!(stdout, MoveTo(0, 0))?;
+    let status = format!("Score: {}  Length: {}", game.score, game.length());
+    queue!(
+        stdout,
+        Print(status),
+        Clear(ClearType::UntilNewLine),
+        Print("\r\n")
+    )?;
+
+    for y in (-half_height..=half_height).rev() {
+        let row: String = (-half_width..=half_width)
+            .map(|x| {
+                let position = Position { x, y };
+                if position == game.snake.head {
+                    glyphs.head
+                } else if game.snake.tail.contains(&position) {
+                    glyphs.tail
+                } else if game.food.contains(&position) {
+                    glyphs.food
+                } else if hits_wall(game.arena, position) {
+                    glyphs.wall
+                } else {
+                    glyphs.empty
+                }
+            })
+            .collect();
+        queue!(
+            stdout,
+            Print(row),
+            Clear(ClearType::UntilNewLine),
+            Print("\r\n")
+        )?;
+    }
+
+    queue!(
+        stdout,
+        Print(prompt(game, paused)),
+        Clear(ClearType::FromCursorDown)
+    )?;
+    stdout.flush()
+}
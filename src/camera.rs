@@ -0,0 +1,145 @@
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::arena::ArenaSize;
+use crate::game_loop::SnakeHead;
+use crate::game_state::GameState;
+use crate::hud::HUD_HEIGHT;
+use crate::layout::{Layout, LayoutScaled, LayoutSystem};
+use crate::primitives::{PositionSystem, Scaling};
+use crate::settings::{CameraMode, Settings};
+
+/// Half the size of the box around the camera centre, in cells, that the head
+/// can move in without dragging the camera along.
+const DEAD_ZONE: (f32, f32) = (3.0, 2.0);
+const SMOOTHING: f32 = 6.0;
+const SHAKE_CELLS: f32 = 0.6;
+const SHAKE_DECAY: f32 = 2.0;
+
+pub struct CameraPlugin;
+
+impl Plugin for CameraPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CameraRig>()
+            .add_system_set(SystemSet::on_enter(GameState::Dying).with_system(shake_camera))
+            .add_system(
+                follow_snake
+                    .label(CameraSystem::Follow)
+                    .after(PositionSystem::Interpolate)
+                    .after(LayoutSystem::Update),
+            )
+            .add_system(anchor_to_camera.after(CameraSystem::Follow));
+    }
+}
+
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, SystemLabel)]
+pub enum CameraSystem {
+    Follow,
+}
+
+#[derive(Component)]
+pub struct GameCamera;
+
+#[derive(Default)]
+struct CameraRig {
+    focus: Vec2,
+    position: Vec2,
+    trauma: f32,
+}
+
+fn shake_camera(settings: Res<Settings>, mut rig: ResMut<CameraRig>) {
+    if settings.video.screen_shake {
+        rig.trauma = 1.0;
+    }
+}
+
+/// How far the camera centre may move from the origin before the view would
+/// show past the arena's edge, in world units.
+fn camera_bounds(layout: &Layout, arena: &ArenaSize, scale: f32) -> Vec2 {
+    let arena = Vec2::new(arena.width as f32 + 2.0, arena.height as f32 + 2.0) * scale / 2.0;
+    let view = Vec2::new(
+        layout.viewport.x / 2.0,
+        layout.viewport.y / 2.0 - HUD_HEIGHT * layout.scale,
+    );
+
+    (arena - view).max(Vec2::ZERO)
+}
+
+/// The snake heads the camera follows and the cameras that follow them.
+#[derive(SystemParam)]
+struct Tracking<'w, 's> {
+    heads: Query<'w, 's, &'static Transform, (With<SnakeHead>, Without<GameCamera>)>,
+    cameras: Query<'w, 's, &'static mut Transform, With<GameCamera>>,
+}
+
+fn follow_snake(
+    time: Res<Time>,
+    settings: Res<Settings>,
+    layout: Res<Layout>,
+    scaling: Res<Scaling>,
+    arena: Res<ArenaSize>,
+    mut rig: ResMut<CameraRig>,
+    mut tracking: Tracking,
+) {
+    let delta = time.delta_seconds();
+    let bounds = camera_bounds(&layout, &arena, scaling.0);
+
+    match settings.video.camera {
+        CameraMode::Fit => {
+            rig.focus = Vec2::ZERO;
+            rig.position = Vec2::ZERO;
+        }
+        CameraMode::Follow => {
+            if let Some(head) = tracking.heads.iter().next() {
+                let target = head.translation.truncate();
+                let dead_zone = Vec2::from(DEAD_ZONE) * scaling.0;
+                let offset = target - rig.focus;
+                let outside = offset.abs() - dead_zone;
+                let mut focus = rig.focus + offset.signum() * outside.max(Vec2::ZERO);
+                focus = focus.clamp(-bounds, bounds);
+                rig.focus = focus;
+            } else {
+                rig.focus = Vec2::ZERO;
+            }
+
+            let step = 1.0 - (-SMOOTHING * delta).exp();
+            let position = rig.position + (rig.focus - rig.position) * step;
+            rig.position = position.clamp(-bounds, bounds);
+        }
+    }
+
+    let mut shake = Vec2::ZERO;
+    if rig.trauma > 0.0 {
+        let mut rng = rand::thread_rng();
+        let strength = rig.trauma * rig.trauma * SHAKE_CELLS * scaling.0;
+        shake = Vec2::new(rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0)) * strength;
+        rig.trauma = (rig.trauma - SHAKE_DECAY * delta).max(0.0);
+    }
+
+    let translation = rig.position + shake;
+    for mut transform in tracking.cameras.iter_mut() {
+        if transform.translation.truncate() != translation {
+            transform.translation.x = translation.x;
+            transform.translation.y = translation.y;
+        }
+    }
+}
+
+/// Keeps screen-space text such as menus and the countdown centred on the
+/// camera instead of the arena.
+fn anchor_to_camera(
+    rig: Res<CameraRig>,
+    mut anchored: Query<&mut Transform, (With<LayoutScaled>, Without<Parent>)>,
+) {
+    if !rig.is_changed() {
+        return;
+    }
+
+    for mut transform in anchored.iter_mut() {
+        if transform.translation.truncate() != rig.position {
+            transform.translation.x = rig.position.x;
+            transform.translation.y = rig.position.y;
+        }
+    }
+}
@@ -0,0 +1,142 @@
+use bevy::asset::{AssetLoader, LoadContext, LoadedAsset};
+use bevy::prelude::*;
+use bevy::reflect::TypeUuid;
+use bevy::utils::BoxedFuture;
+
+use crate::settings::{GameplaySettings, SavedGameplay, Settings};
+use crate::theme::{ActiveTheme, ThemeColor};
+
+const GAMEPLAY_CONFIG_PATH: &str = "config/gameplay.ron";
+
+pub struct ConfigPlugin;
+
+impl Plugin for ConfigPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<GameplayConfig>()
+            .init_asset_loader::<GameplayConfigLoader>();
+
+        if cfg!(debug_assertions) {
+            app.add_startup_system(load_gameplay_config)
+                .add_startup_system(spawn_error_text)
+                .add_system(apply_gameplay_config);
+        }
+    }
+}
+
+#[derive(TypeUuid)]
+#[uuid = "5b0f3d8e-6c3a-4f7e-9a43-0c2d7b1e8f61"]
+pub struct GameplayConfig(Result<GameplaySettings, String>);
+
+#[derive(Default)]
+struct GameplayConfigLoader;
+
+impl AssetLoader for GameplayConfigLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+        Box::pin(async move {
+            let config = ron::de::from_bytes::<GameplaySettings>(bytes)
+                .map_err(|e| e.to_string())
+                .and_then(|mut gameplay| {
+                    gameplay.round_arena();
+                    gameplay.validate().map(|_| gameplay)
+                });
+
+            load_context.set_default_asset(LoadedAsset::new(GameplayConfig(config)));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["gameplay.ron"]
+    }
+}
+
+struct GameplayConfigHandle(Handle<GameplayConfig>);
+
+#[derive(Component)]
+struct ConfigErrorText;
+
+fn load_gameplay_config(mut commands: Commands, asset_server: Res<AssetServer>) {
+    if let Err(error) = asset_server.watch_for_changes() {
+        warn!("hot-reloading is unavailable: {:?}", error);
+    }
+
+    commands.insert_resource(GameplayConfigHandle(
+        asset_server.load(GAMEPLAY_CONFIG_PATH),
+    ));
+}
+
+fn spawn_error_text(mut commands: Commands, theme: Res<ActiveTheme>) {
+    let text_style = TextStyle {
+        font: theme.font.clone(),
+        font_size: 20.0,
+        color: theme.palette.error,
+    };
+
+    commands
+        .spawn_bundle(TextBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: Rect {
+                    top: Val::Px(5.0),
+                    left: Val::Px(5.0),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            text: Text::with_section("", text_style, Default::default()),
+            ..Default::default()
+        })
+        .insert(ConfigErrorText)
+        .insert(ThemeColor::Error);
+}
+
+/// Applies edits to the dev config as they are saved. The version found at
+/// startup is only checked, and the player's own gameplay settings are kept
+/// aside so the edits never reach settings.ron.
+fn apply_gameplay_config(
+    mut commands: Commands,
+    saved: Option<Res<SavedGameplay>>,
+    mut events: EventReader<AssetEvent<GameplayConfig>>,
+    configs: Res<Assets<GameplayConfig>>,
+    handle: Res<GameplayConfigHandle>,
+    mut settings: ResMut<Settings>,
+    mut errors: Query<&mut Text, With<ConfigErrorText>>,
+) {
+    for event in events.iter() {
+        let (changed, modified) = match event {
+            AssetEvent::Created { handle } => (handle, false),
+            AssetEvent::Modified { handle } => (handle, true),
+            AssetEvent::Removed { .. } => continue,
+        };
+
+        if *changed != handle.0 {
+            continue;
+        }
+
+        let message = match configs.get(changed).map(|config| &config.0) {
+            Some(Ok(gameplay)) => {
+                if modified && settings.gameplay != *gameplay {
+                    if saved.is_none() {
+                        commands.insert_resource(SavedGameplay(settings.gameplay.clone()));
+                    }
+                    settings.gameplay = gameplay.clone();
+                }
+                String::new()
+            }
+            Some(Err(error)) => format!("{}: {}", GAMEPLAY_CONFIG_PATH, error),
+            None => continue,
+        };
+
+        if !message.is_empty() {
+            error!("{}", message);
+        }
+
+        for mut text in errors.iter_mut() {
+            text.sections[0].value = message.clone();
+        }
+    }
+}
@@ -0,0 +1,111 @@
+use bevy::prelude::*;
+
+use crate::game_state::{CleanUp, GameState};
+use crate::layout::LayoutScaled;
+use crate::locale::{Localization, Message};
+use crate::settings::Settings;
+use crate::theme::{ActiveTheme, ThemeColor};
+
+const GO_SECONDS: f32 = 0.5;
+
+pub struct CountdownPlugin;
+
+impl Plugin for CountdownPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(
+            SystemSet::on_enter(GameState::MainGameLoop).with_system(start_countdown),
+        )
+        .add_system_set(SystemSet::on_enter(GameState::Countdown).with_system(spawn_countdown))
+        .add_system_set(SystemSet::on_update(GameState::Countdown).with_system(tick_countdown));
+    }
+}
+
+#[derive(Component)]
+struct Countdown {
+    timer: Timer,
+}
+
+impl Countdown {
+    fn label(&self) -> Message {
+        let remaining = self.timer.duration().as_secs_f32() - self.timer.elapsed_secs();
+        if remaining > GO_SECONDS {
+            Message::raw((remaining - GO_SECONDS).ceil() as u32)
+        } else {
+            Message::new("countdown.go")
+        }
+    }
+}
+
+pub fn resume_game(state: &mut State<GameState>, settings: &Settings) {
+    if settings.gameplay.countdown_seconds > 0 {
+        state.set(GameState::Countdown).unwrap();
+    } else {
+        state.pop().unwrap();
+    }
+}
+
+fn start_countdown(settings: Res<Settings>, mut state: ResMut<State<GameState>>) {
+    if settings.gameplay.countdown_seconds > 0 {
+        state.push(GameState::Countdown).unwrap();
+    }
+}
+
+fn spawn_countdown(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    theme: Res<ActiveTheme>,
+    localization: Res<Localization>,
+) {
+    let countdown = Countdown {
+        timer: Timer::from_seconds(
+            settings.gameplay.countdown_seconds as f32 + GO_SECONDS,
+            false,
+        ),
+    };
+    let text_style = TextStyle {
+        font: theme.font.clone(),
+        font_size: theme.sizes.countdown,
+        color: theme.palette.text,
+    };
+    let text_alignment = TextAlignment {
+        vertical: VerticalAlign::Center,
+        horizontal: HorizontalAlign::Center,
+    };
+
+    commands
+        .spawn_bundle(Text2dBundle {
+            text: Text::with_section(
+                localization.resolve(&countdown.label()),
+                text_style,
+                text_alignment,
+            ),
+            transform: Transform {
+                translation: Vec3::new(0.0, 0.0, 2.1),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .insert(countdown)
+        .insert(LayoutScaled)
+        .insert(ThemeColor::Text)
+        .insert(CleanUp::new(GameState::Countdown));
+}
+
+fn tick_countdown(
+    time: Res<Time>,
+    mut state: ResMut<State<GameState>>,
+    localization: Res<Localization>,
+    mut countdowns: Query<(&mut Countdown, &mut Text)>,
+) {
+    for (mut countdown, mut text) in countdowns.iter_mut() {
+        if countdown.timer.tick(time.delta()).finished() {
+            state.pop().unwrap();
+            return;
+        }
+
+        let label = localization.resolve(&countdown.label());
+        if text.sections[0].value != label {
+            text.sections[0].value = label;
+        }
+    }
+}
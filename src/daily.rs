@@ -0,0 +1,161 @@
+use std::fs;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::arena::ArenaSize;
+use crate::game_loop::{FoodRules, GameStage, Score};
+use crate::game_state::{GameMode, GameState};
+use crate::replay::Seed;
+
+const LEADERBOARD_PATH: &str = "daily.ron";
+const SECONDS_PER_DAY: u64 = 60 * 60 * 24;
+
+pub struct DailyPlugin;
+
+impl Plugin for DailyPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(DailyLeaderboard::load())
+            .add_system_set(
+                SystemSet::on_enter(GameState::MainGameLoop)
+                    .with_system(configure_daily.label(GameStage::Setup)),
+            )
+            .add_system_set(SystemSet::on_enter(GameState::GameOver).with_system(record_score));
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DailyChallenge {
+    pub day: u64,
+    pub seed: u64,
+    pub arena: ArenaSize,
+    pub food: FoodRules,
+}
+
+impl DailyChallenge {
+    pub fn today() -> Self {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        DailyChallenge::for_day(now.as_secs() / SECONDS_PER_DAY)
+    }
+
+    pub fn for_day(day: u64) -> Self {
+        let seed = mix(day);
+        let size = |x: u64, min: u64, max: u64| (min + x % (max - min + 1)) as i32 * 2 + 1;
+
+        DailyChallenge {
+            day,
+            seed,
+            arena: ArenaSize {
+                width: size(mix(seed ^ 1), 7, 14),
+                height: size(mix(seed ^ 2), 7, 14),
+            },
+            food: FoodRules {
+                count: 1 + (mix(seed ^ 3) % 3) as usize,
+                growth: 1 + (mix(seed ^ 4) % 3) as usize,
+            },
+        }
+    }
+
+    pub fn date(&self) -> String {
+        let z = self.day as i64 + 719468;
+        let era = z.div_euclid(146097);
+        let doe = z.rem_euclid(146097);
+        let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = doy - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+        format!("{:04}-{:02}-{:02}", year, month, day)
+    }
+}
+
+fn mix(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailyEntry {
+    pub day: u64,
+    pub date: String,
+    pub score: usize,
+    /// Set once the day's scored attempt has ended, after which the score is final.
+    #[serde(default)]
+    pub finished: bool,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct DailyLeaderboard {
+    pub entries: Vec<DailyEntry>,
+}
+
+impl DailyLeaderboard {
+    fn load() -> Self {
+        fs::read_to_string(LEADERBOARD_PATH)
+            .ok()
+            .and_then(|contents| ron::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self) {
+        let result = ron::to_string(self)
+            .map_err(|e| e.to_string())
+            .and_then(|contents| fs::write(LEADERBOARD_PATH, contents).map_err(|e| e.to_string()));
+
+        if let Err(error) = result {
+            error!("failed to save daily leaderboard: {}", error);
+        }
+    }
+
+    pub fn entry(&self, day: u64) -> Option<&DailyEntry> {
+        self.entries.iter().find(|entry| entry.day == day)
+    }
+
+    fn entry_mut(&mut self, day: u64) -> Option<&mut DailyEntry> {
+        self.entries.iter_mut().find(|entry| entry.day == day)
+    }
+}
+
+fn configure_daily(
+    mode: Res<GameMode>,
+    mut leaderboard: ResMut<DailyLeaderboard>,
+    mut seed: ResMut<Seed>,
+    mut arena: ResMut<ArenaSize>,
+    mut rules: ResMut<FoodRules>,
+) {
+    if let GameMode::Daily(day) | GameMode::DailyPractice(day) = *mode {
+        let challenge = DailyChallenge::for_day(day);
+        seed.0 = challenge.seed;
+        *arena = challenge.arena;
+        *rules = challenge.food;
+
+        if *mode == GameMode::Daily(day) && leaderboard.entry(day).is_none() {
+            leaderboard.entries.push(DailyEntry {
+                day,
+                date: challenge.date(),
+                score: 0,
+                finished: false,
+            });
+            leaderboard.save();
+        }
+    }
+}
+
+fn record_score(score: Res<Score>, mode: Res<GameMode>, mut leaderboard: ResMut<DailyLeaderboard>) {
+    if let GameMode::Daily(day) = *mode {
+        match leaderboard.entry_mut(day) {
+            Some(entry) if !entry.finished => {
+                entry.score = score.0;
+                entry.finished = true;
+            }
+            _ => return,
+        }
+        leaderboard.save();
+    }
+}
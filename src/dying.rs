@@ -0,0 +1,146 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+
+use crate::game_loop::SnakeHead;
+use crate::game_state::{CleanUp, GameState};
+use crate::primitives::{Position, Rec};
+use crate::theme::{ActiveTheme, ThemeColor};
+
+const FLASH_SECONDS: f32 = 0.1;
+const COLLAPSE_SECONDS: f32 = 0.08;
+const MAX_COLLAPSE_SECONDS: f32 = 1.5;
+const HOLD_SECONDS: f32 = 0.5;
+
+pub struct DyingPlugin;
+
+impl Plugin for DyingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<CollapseEvent>()
+            .add_system_set(SystemSet::on_enter(GameState::Dying).with_system(start_dying))
+            .add_system_set(SystemSet::on_update(GameState::Dying).with_system(animate_dying))
+            .add_system_set(SystemSet::on_exit(GameState::Dying).with_system(finish_dying));
+    }
+}
+
+/// Sent for every segment as it disappears during the death animation.
+pub struct CollapseEvent {
+    pub position: Position,
+}
+
+#[derive(Component)]
+struct Dying {
+    snake: Entity,
+    /// Segments still to collapse, tail tip first.
+    segments: VecDeque<Entity>,
+    collapse: Timer,
+    flash: Timer,
+    hold: Timer,
+    lit: bool,
+}
+
+fn start_dying(
+    mut commands: Commands,
+    theme: Res<ActiveTheme>,
+    heads: Query<(Entity, &SnakeHead, &Position)>,
+) {
+    for (snake, head, position) in heads.iter() {
+        let interval = COLLAPSE_SECONDS.min(MAX_COLLAPSE_SECONDS / head.tail.len().max(1) as f32);
+
+        commands
+            .spawn()
+            .insert(Dying {
+                snake,
+                segments: head.tail.clone(),
+                collapse: Timer::from_seconds(interval, true),
+                flash: Timer::from_seconds(FLASH_SECONDS, true),
+                hold: Timer::from_seconds(HOLD_SECONDS, false),
+                lit: true,
+            })
+            .insert(CleanUp::new(GameState::Dying));
+
+        // The cell the head ran into stays marked underneath the game-over screen,
+        // and below the replay viewer opened from it.
+        commands
+            .spawn_bundle(SpriteBundle {
+                sprite: Sprite {
+                    color: theme.palette.error,
+                    ..Default::default()
+                },
+                transform: Transform {
+                    translation: Vec3::new(0.0, 0.0, 1.6),
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .insert(ThemeColor::Error)
+            .insert(*position)
+            .insert(Rec {
+                width: 1,
+                height: 1,
+            })
+            .insert(CleanUp::new(GameState::MainGameLoop));
+    }
+}
+
+fn animate_dying(
+    time: Res<Time>,
+    theme: Res<ActiveTheme>,
+    mut state: ResMut<State<GameState>>,
+    mut events: EventWriter<CollapseEvent>,
+    mut dying: Query<&mut Dying>,
+    mut segments: Query<(&Position, &mut TextureAtlasSprite, &mut Visibility)>,
+) {
+    // The Dying entities only exist once the commands from `start_dying` apply.
+    let mut started = false;
+    let mut finished = true;
+
+    for mut dying in dying.iter_mut() {
+        started = true;
+        if dying.flash.tick(time.delta()).just_finished() {
+            dying.lit = !dying.lit;
+            let color = if dying.lit {
+                theme.palette.snake
+            } else {
+                theme.palette.error
+            };
+            for entity in dying.segments.iter().chain(Some(&dying.snake)) {
+                if let Ok((_, mut sprite, _)) = segments.get_mut(*entity) {
+                    sprite.color = color;
+                }
+            }
+        }
+
+        if !dying.segments.is_empty() {
+            finished = false;
+            if dying.collapse.tick(time.delta()).just_finished() {
+                let entity = dying.segments.pop_front().unwrap();
+                if let Ok((position, _, mut visibility)) = segments.get_mut(entity) {
+                    visibility.is_visible = false;
+                    events.send(CollapseEvent {
+                        position: *position,
+                    });
+                }
+            }
+        } else if !dying.hold.tick(time.delta()).finished() {
+            finished = false;
+        }
+    }
+
+    if started && finished {
+        state.set(GameState::GameOver).unwrap();
+    }
+}
+
+fn finish_dying(
+    theme: Res<ActiveTheme>,
+    mut keys: ResMut<Input<KeyCode>>,
+    mut heads: Query<&mut TextureAtlasSprite, With<SnakeHead>>,
+) {
+    for mut sprite in heads.iter_mut() {
+        sprite.color = theme.palette.snake;
+    }
+
+    // Keys pressed while the snake was dying must not confirm the game-over menu.
+    keys.clear();
+}
@@ -1,10 +1,30 @@
 use bevy::prelude::*;
 
-use crate::primitives::ProgressBar;
+use crate::game_state::GameState;
+
+const EXPERIENCE_PER_LEVEL: usize = 10;
 
 #[derive(Debug, Default)]
 pub struct Experience(pub usize);
 
+pub fn level_for(points: usize) -> usize {
+    points / EXPERIENCE_PER_LEVEL + 1
+}
+
+impl Experience {
+    pub fn level(&self) -> usize {
+        level_for(self.0)
+    }
+
+    pub fn progress(&self) -> f32 {
+        (self.0 % EXPERIENCE_PER_LEVEL) as f32 / EXPERIENCE_PER_LEVEL as f32
+    }
+}
+
+/// Sent once when experience reaches a new level, carrying that level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LevelUpEvent(pub usize);
+
 #[derive(Component)]
 pub struct ExperienceBar;
 
@@ -13,29 +33,37 @@ pub struct PauseMenuPlugin;
 impl Plugin for PauseMenuPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<Experience>()
+            .add_event::<LevelUpEvent>()
+            .add_system_set(
+                SystemSet::on_enter(GameState::MainGameLoop).with_system(reset_experience),
+            )
             .add_system(update_experience_bars)
             .add_system(init_experience_bars);
     }
 }
 
+fn reset_experience(mut experience: ResMut<Experience>) {
+    experience.0 = 0;
+}
+
 fn update_experience_bars(
     experience: Res<Experience>,
-    mut bars: Query<&mut ProgressBar, With<ExperienceBar>>,
+    mut bars: Query<&mut Style, With<ExperienceBar>>,
 ) {
     if experience.is_changed() {
-        for mut bar in bars.iter_mut() {
-            bar.percent = experience.0 as f32 / 100.0;
+        for mut style in bars.iter_mut() {
+            style.size.width = Val::Percent(experience.progress() * 100.0);
         }
     }
 }
 
 fn init_experience_bars(
     experience: Res<Experience>,
-    mut bars: Query<&mut ProgressBar, Added<ExperienceBar>>,
+    mut bars: Query<&mut Style, Added<ExperienceBar>>,
 ) {
     if !experience.is_changed() {
-        for mut bar in bars.iter_mut() {
-            bar.percent = experience.0 as f32 / 100.0;
+        for mut style in bars.iter_mut() {
+            style.size.width = Val::Percent(experience.progress() * 100.0);
         }
     }
 }
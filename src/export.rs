@@ -0,0 +1,210 @@
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::path::Path;
+
+use bevy::prelude::Color;
+
+use crate::primitives::{Position, Rec};
+use crate::replay::{Playback, Replay};
+use crate::theme::Palette;
+
+pub struct Canvas {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+impl Canvas {
+    fn new(width: u32, height: u32, color: Color) -> Self {
+        let pixels = rgb(color)
+            .iter()
+            .copied()
+            .cycle()
+            .take((width * height * 3) as usize)
+            .collect();
+
+        Canvas {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    fn fill(&mut self, x: u32, y: u32, width: u32, height: u32, color: Color) {
+        let color = rgb(color);
+        for row in y..(y + height).min(self.height) {
+            for column in x..(x + width).min(self.width) {
+                let index = ((row * self.width + column) * 3) as usize;
+                self.pixels[index..index + 3].copy_from_slice(&color);
+            }
+        }
+    }
+}
+
+fn rgb(color: Color) -> [u8; 3] {
+    let [r, g, b, _] = color.as_rgba_f32();
+    [r, g, b].map(|x| (x.clamp(0.0, 1.0) * 255.0).round() as u8)
+}
+
+pub struct Rasterizer {
+    arena: Rec,
+    cell: u32,
+    palette: Palette,
+}
+
+impl Rasterizer {
+    pub fn new(replay: &Replay, cell: u32, palette: &Palette) -> Self {
+        Rasterizer {
+            arena: Rec {
+                width: replay.arena_width,
+                height: replay.arena_height,
+            },
+            cell,
+            palette: *palette,
+        }
+    }
+
+    fn columns(&self) -> i32 {
+        self.arena.width + 2
+    }
+
+    fn rows(&self) -> i32 {
+        self.arena.height + 2
+    }
+
+    /// The size of each frame in pixels, or an error if cells are empty or
+    /// frames that big can't be held in memory.
+    fn size(&self) -> Result<(u32, u32), String> {
+        if self.cell == 0 {
+            return Err("cells must be at least 1 pixel".to_string());
+        }
+
+        let too_large = || format!("a cell size of {} makes frames too large", self.cell);
+        let width = (self.columns() as u32)
+            .checked_mul(self.cell)
+            .ok_or_else(too_large)?;
+        let height = (self.rows() as u32)
+            .checked_mul(self.cell)
+            .ok_or_else(too_large)?;
+        width
+            .checked_mul(height)
+            .and_then(|pixels| pixels.checked_mul(3))
+            .ok_or_else(too_large)?;
+        Ok((width, height))
+    }
+
+    fn fill_cell(&self, canvas: &mut Canvas, position: Position, color: Color) {
+        let column = position.x + self.columns() / 2;
+        let row = self.rows() / 2 - position.y;
+        if column < 0 || row < 0 || column >= self.columns() || row >= self.rows() {
+            return;
+        }
+
+        canvas.fill(
+            column as u32 * self.cell,
+            row as u32 * self.cell,
+            self.cell,
+            self.cell,
+            color,
+        );
+    }
+
+    pub fn draw(&self, playback: &Playback, food: &[Position]) -> Canvas {
+        let mut canvas = Canvas::new(
+            self.columns() as u32 * self.cell,
+            self.rows() as u32 * self.cell,
+            self.palette.background,
+        );
+
+        let half_width = self.columns() / 2;
+        let half_height = self.rows() / 2;
+        for x in -half_width..=half_width {
+            for y in -half_height..=half_height {
+                let position = Position { x, y };
+                if self.arena.contains(position) {
+                    self.fill_cell(&mut canvas, position, self.palette.arena);
+                }
+            }
+        }
+
+        for position in food {
+            self.fill_cell(&mut canvas, *position, self.palette.food);
+        }
+
+        for position in playback.tail.iter().chain(Some(&playback.head)) {
+            self.fill_cell(&mut canvas, *position, self.palette.snake);
+        }
+
+        canvas
+    }
+
+    pub fn frames<'a>(&'a self, replay: &'a Replay) -> impl Iterator<Item = Canvas> + 'a {
+        let mut playback = Playback::default();
+        let food = replay.frames.first().map_or(&[][..], |frame| &frame.food);
+        let first = self.draw(&playback, food);
+        let rest = std::iter::from_fn(move || {
+            let frame = playback.step(replay)?;
+            Some(self.draw(&playback, &frame.food))
+        });
+
+        Some(first).into_iter().chain(rest)
+    }
+}
+
+pub fn export_gif(
+    replay: &Replay,
+    palette: &Palette,
+    cell: u32,
+    delay: u16,
+    path: impl AsRef<Path>,
+) -> Result<(), String> {
+    let rasterizer = Rasterizer::new(replay, cell, palette);
+    let (width, height) = rasterizer.size()?;
+    let too_large = |_| format!("a cell size of {} is too large for a GIF", cell);
+    let width = u16::try_from(width).map_err(too_large)?;
+    let height = u16::try_from(height).map_err(too_large)?;
+
+    let file = File::create(path).map_err(|e| e.to_string())?;
+    let mut encoder =
+        gif::Encoder::new(BufWriter::new(file), width, height, &[]).map_err(|e| e.to_string())?;
+    encoder
+        .set_repeat(gif::Repeat::Infinite)
+        .map_err(|e| e.to_string())?;
+
+    for canvas in rasterizer.frames(replay) {
+        let mut frame = gif::Frame::from_rgb(width, height, &canvas.pixels);
+        frame.delay = delay;
+        encoder.write_frame(&frame).map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+pub fn export_png_sequence(
+    replay: &Replay,
+    palette: &Palette,
+    cell: u32,
+    directory: impl AsRef<Path>,
+) -> Result<(), String> {
+    let rasterizer = Rasterizer::new(replay, cell, palette);
+    rasterizer.size()?;
+
+    let directory = directory.as_ref();
+    fs::create_dir_all(directory).map_err(|e| e.to_string())?;
+
+    for (index, canvas) in rasterizer.frames(replay).enumerate() {
+        let path = directory.join(format!("frame-{:05}.png", index));
+        let file = File::create(path).map_err(|e| e.to_string())?;
+
+        let mut encoder = png::Encoder::new(BufWriter::new(file), canvas.width, canvas.height);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        let mut writer = encoder.write_header().map_err(|e| e.to_string())?;
+        writer
+            .write_image_data(&canvas.pixels)
+            .map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
@@ -2,143 +2,207 @@ use std::collections::VecDeque;
 use std::time::Duration;
 
 use bevy::prelude::*;
-use rand::Rng;
 
 use crate::arena::*;
-use crate::experience::{Experience, ExperienceBar};
-use crate::game_state::{CleanUp, GameState};
+use crate::experience::{Experience, LevelUpEvent};
+use crate::game_state::{CleanUp, GameMode, GameState};
+use crate::high_scores::HighScores;
+use crate::markers::MarkerShape;
 use crate::primitives::Direction;
 use crate::primitives::*;
+use crate::replay::GameRng;
+use crate::rules::{self, DeathCause, Snake};
+use crate::settings::Settings;
+use crate::snake_sprites::{SegmentSprite, SnakeAtlas};
+use crate::theme::{ActiveTheme, ThemeColor};
 
 pub struct GameLoopPlugin;
 
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, SystemLabel)]
 pub enum GameStage {
+    Setup,
     Movement,
     Collection,
 }
 
 #[derive(Debug, Default)]
-struct Score(usize);
+pub struct Score(pub usize);
+
+#[derive(Debug, Default)]
+pub struct RunStats {
+    pub time: Duration,
+    pub length: usize,
+    pub best: usize,
+    pub cause: Option<DeathCause>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FoodRules {
+    pub count: usize,
+    pub growth: usize,
+}
+
+impl Default for FoodRules {
+    fn default() -> Self {
+        FoodRules {
+            count: 1,
+            growth: 1,
+        }
+    }
+}
 
 impl Plugin for GameLoopPlugin {
     fn build(&self, app: &mut App) {
         use GameStage::*;
 
         app.add_event::<CollectEvent>()
+            .add_event::<TickEvent>()
             .init_resource::<Score>()
+            .init_resource::<RunStats>()
+            .init_resource::<ArenaSize>()
+            .init_resource::<FoodRules>()
             .add_system_set(
                 SystemSet::on_enter(GameState::MainGameLoop)
+                    .with_system(configure_run.label(Setup))
                     .with_system(spawn_snake)
-                    .with_system(spawn_arena)
-                    .with_system(spawn_score_board)
-                    .with_system(spawn_experience_bar)
-                    .with_system(reset_score),
+                    .with_system(spawn_arena.after(Setup))
+                    .with_system(reset_score)
+                    .with_system(reset_stats),
             )
             .add_system_set(
                 SystemSet::on_update(GameState::MainGameLoop)
-                    .with_system(move_snake_head.chain(move_snake_tail).label(Movement))
+                    // Food is eaten as part of the move, so both stages end here.
+                    .with_system(
+                        move_snake_head
+                            .chain(move_snake_tail)
+                            .label(Movement)
+                            .label(Collection),
+                    )
+                    .with_system(
+                        track_interpolation
+                            .after(Movement)
+                            .before(PositionSystem::Interpolate),
+                    )
+                    .with_system(apply_gameplay_settings)
+                    .with_system(track_time)
                     .with_system(spawn_apple)
                     .with_system(snake_controls)
-                    .with_system(kill_snake_outside_arena)
-                    .with_system(kill_snake_hitting_tail)
-                    .with_system(update_score_board)
-                    .with_system(collect_food.after(Movement).label(Collection))
+                    .with_system(track_death.after(Movement))
                     .with_system(despawn_food.after(Collection))
-                    .with_system(grow_snake.after(Collection))
+                    .with_system(track_length.after(Collection))
                     .with_system(track_score.after(Collection))
                     .with_system(track_experience.after(Collection))
                     .with_system(pause_game.chain(game_over)),
-            );
+            )
+            .add_system_set(SystemSet::on_update(GameState::Countdown).with_system(snake_controls));
     }
 }
 
-const SNAKE_HEAD_COLOR: Color = Color::rgb(0.0, 0.7, 0.7);
-const FOOD_COLOR: Color = Color::rgb(0.0, 0.7, 0.0);
-const EXPERIENCE_BAR_COLOR: Color = Color::rgb(1.0, 1.0, 1.0);
+pub const SNAKE_HEAD_COLOR: Color = Color::rgb(0.0, 0.7, 0.7);
+pub const FOOD_COLOR: Color = Color::rgb(0.0, 0.7, 0.0);
+pub const EXPERIENCE_BAR_COLOR: Color = Color::rgb(1.0, 1.0, 1.0);
 
 #[derive(Component, Default)]
-struct SnakeHead {
-    direction: Direction,
-    heading: Direction,
-    tail: VecDeque<Entity>,
-    length: usize,
-    last_position: Position,
-    dead: bool,
+pub struct SnakeHead {
+    pub body: Snake,
+    /// Entities for `body.tail`, in the same order.
+    pub tail: VecDeque<Entity>,
 }
 
 #[derive(Component)]
 struct Food;
 
-#[derive(Component)]
-struct Tail;
+pub struct TickEvent {
+    pub snake: Entity,
+    pub heading: Direction,
+    pub position: Position,
+    pub tail: usize,
+    pub food: Vec<Position>,
+}
 
 #[derive(Component)]
-struct ScoreBoard;
+struct Tail;
 
 fn reset_score(mut score: ResMut<Score>) {
     score.0 = 0;
 }
 
-pub fn spawn_arena(mut commands: Commands) {
+fn reset_stats(high_scores: Res<HighScores>, mut stats: ResMut<RunStats>) {
+    *stats = RunStats {
+        length: 1,
+        best: high_scores.best(),
+        ..Default::default()
+    };
+}
+
+fn track_time(time: Res<Time>, mut stats: ResMut<RunStats>) {
+    stats.time += time.delta();
+}
+
+fn configure_run(
+    settings: Res<Settings>,
+    mode: Res<GameMode>,
+    mut arena: ResMut<ArenaSize>,
+    mut rules: ResMut<FoodRules>,
+) {
+    if *mode == GameMode::Classic {
+        *arena = settings.gameplay.arena();
+        *rules = settings.gameplay.food_rules();
+    }
+}
+
+fn apply_gameplay_settings(
+    settings: Res<Settings>,
+    mode: Res<GameMode>,
+    mut rules: ResMut<FoodRules>,
+    mut timers: Query<&mut Timer, With<SnakeHead>>,
+) {
+    if settings.is_changed() {
+        for mut timer in timers.iter_mut() {
+            timer.set_duration(Duration::from_millis(settings.gameplay.tick_millis));
+        }
+
+        if *mode == GameMode::Classic {
+            *rules = settings.gameplay.food_rules();
+        }
+    }
+}
+
+pub fn spawn_arena(mut commands: Commands, arena: Res<ArenaSize>, theme: Res<ActiveTheme>) {
     commands
         .spawn_bundle(SpriteBundle {
             sprite: Sprite {
-                color: ARENA_COLOR,
+                color: theme.palette.arena,
                 ..Default::default()
             },
             ..Default::default()
         })
         .insert(Arena)
+        .insert(ThemeColor::Arena)
         .insert(Position { x: 0, y: 0 })
         .insert(Rec {
-            width: ARENA_WIDTH as i32,
-            height: ARENA_HEIGHT as i32,
+            width: arena.width,
+            height: arena.height,
         })
         .insert(CleanUp::new(GameState::MainGameLoop));
 }
 
-fn spawn_score_board(mut commands: Commands, asset_server: Res<AssetServer>) {
-    let font = asset_server.load("fonts/FiraSans-Bold.ttf");
-    let text_style = TextStyle {
-        font,
-        font_size: 60.0,
-        color: Color::WHITE,
-    };
-    let text_alignment = TextAlignment {
-        vertical: VerticalAlign::Center,
-        horizontal: HorizontalAlign::Center,
-    };
-
-    commands
-        .spawn_bundle(Text2dBundle {
-            text: Text::with_section("0", text_style, text_alignment),
-            transform: Transform {
-                translation: Vec3::new(0.0, 0.0, 1.1),
-                ..Default::default()
-            },
-            ..Default::default()
-        })
-        .insert(ScoreBoard)
-        .insert(Position { x: 0, y: 0 })
-        .insert(CleanUp::new(GameState::MainGameLoop));
-}
-
-fn spawn_snake(mut commands: Commands) {
+fn spawn_snake(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    theme: Res<ActiveTheme>,
+    atlas: Res<SnakeAtlas>,
+) {
     commands
-        .spawn_bundle(SpriteBundle {
-            sprite: Sprite {
-                color: SNAKE_HEAD_COLOR,
-                ..Default::default()
-            },
-            transform: Transform {
-                translation: Vec3::new(0.0, 0.0, 1.0),
-                ..Default::default()
-            },
-            ..Default::default()
-        })
+        .spawn_bundle(atlas.bundle(SegmentSprite::Head, theme.palette.snake))
         .insert(SnakeHead::default())
-        .insert(Timer::new(Duration::from_millis(100), true))
+        .insert(MarkerShape::Dot)
+        .insert(Interpolation::default())
+        .insert(ThemeColor::Snake)
+        .insert(Timer::new(
+            Duration::from_millis(settings.gameplay.tick_millis),
+            true,
+        ))
         .insert(Position { x: 0, y: 0 })
         .insert(Rec {
             width: 1,
@@ -147,91 +211,132 @@ fn spawn_snake(mut commands: Commands) {
         .insert(CleanUp::new(GameState::MainGameLoop));
 }
 
-fn spawn_experience_bar(mut commands: Commands, windows: Res<Windows>) {
-    let window = windows.iter().next().unwrap();
-
-    let width = window.width();
-    let height = window.height();
-
-    commands
-        .spawn_bundle(SpriteBundle {
-            sprite: Sprite {
-                color: EXPERIENCE_BAR_COLOR,
-                ..Default::default()
-            },
-            ..Default::default()
-        })
-        .insert(ProgressBar {
-            bar: Bar {
-                top_right: Vec2::new(width / 2.0, (-height / 2.0) + 20.0),
-                bottom_left: Vec2::new(-width / 2.0, -height / 2.0),
-            },
-            percent: 1.0,
-        })
-        .insert(ExperienceBar)
-        .insert(CleanUp::new(GameState::MainGameLoop));
-}
+fn move_snake_head(
+    time: Res<Time>,
+    arena: Res<ArenaSize>,
+    rules: Res<FoodRules>,
+    mut collects: EventWriter<CollectEvent>,
+    mut ticks: EventWriter<TickEvent>,
+    mut heads: Query<(Entity, &mut SnakeHead, &mut Timer)>,
+    food: Query<(Entity, &Position), With<Food>>,
+) -> Vec<Entity> {
+    let food: Vec<(Entity, Position)> = food
+        .iter()
+        .map(|(entity, position)| (entity, *position))
+        .collect();
+    let positions: Vec<Position> = food.iter().map(|(_, position)| *position).collect();
+    let mut moved = Vec::new();
+
+    for (snake, mut head, mut timer) in heads.iter_mut() {
+        if !timer.tick(time.delta()).just_finished() {
+            continue;
+        }
+        let step = match head.body.step(*arena, *rules, &positions) {
+            Some(step) => step,
+            None => continue,
+        };
 
-fn move_snake_head(time: Res<Time>, mut heads: Query<(&mut SnakeHead, &mut Position, &mut Timer)>) {
-    for (mut head, mut position, mut timer) in heads.iter_mut() {
-        if timer.tick(time.delta()).just_finished() {
-            head.last_position = *position;
-            *position += head.heading.into();
-            head.direction = head.heading;
+        for index in step.eaten {
+            let (food, position) = food[index];
+            collects.send(CollectEvent {
+                food,
+                snake,
+                position,
+            });
         }
+
+        ticks.send(TickEvent {
+            snake,
+            heading: head.body.direction,
+            position: head.body.head,
+            tail: head.body.tail.len(),
+            food: positions.clone(),
+        });
+        moved.push(snake);
     }
+
+    moved
 }
 
 fn move_snake_tail(
+    In(moved): In<Vec<Entity>>,
     mut commands: Commands,
-    mut heads: Query<(&mut SnakeHead, &Timer)>,
-    mut positions: Query<&mut Position>,
+    theme: Res<ActiveTheme>,
+    atlas: Res<SnakeAtlas>,
+    mut heads: Query<&mut SnakeHead>,
+    mut segments: Query<(&mut Position, &mut Interpolation)>,
 ) {
-    for (mut head, timer) in heads.iter_mut() {
-        if timer.just_finished() {
-            let mut last_position = head.last_position;
-            if let Some(entity) = head.tail.pop_front() {
-                let mut position = positions.get_mut(entity).unwrap();
-                head.tail.push_back(entity);
-                std::mem::swap(position.as_mut(), &mut last_position);
+    for snake in moved {
+        let mut head = match heads.get_mut(snake) {
+            Ok(head) => head,
+            Err(_) => continue,
+        };
+        let head = &mut *head;
+
+        if let Ok((mut position, mut interpolation)) = segments.get_mut(snake) {
+            interpolation.previous = *position;
+            *position = head.body.head;
+        }
+
+        // The tail only ever grows at the tip, where it used to end.
+        let grown = head.body.tail.len().saturating_sub(head.tail.len());
+        for position in head.body.tail.iter().take(grown).rev() {
+            let entity = commands
+                .spawn_bundle(atlas.bundle(SegmentSprite::TailTip, theme.palette.snake))
+                .insert(Tail)
+                .insert(ThemeColor::Snake)
+                .insert(*position)
+                .insert(Interpolation {
+                    previous: *position,
+                    progress: 0.0,
+                })
+                .insert(Rec {
+                    width: 1,
+                    height: 1,
+                })
+                .insert(CleanUp::new(GameState::MainGameLoop))
+                .id();
+
+            head.tail.push_front(entity);
+        }
+
+        // Every other segment slides into the cell of the one ahead of it.
+        for (entity, position) in head.tail.iter().zip(&head.body.tail).skip(grown) {
+            if let Ok((mut current, mut interpolation)) = segments.get_mut(*entity) {
+                interpolation.previous = *current;
+                *current = *position;
             }
+        }
+    }
+}
 
-            if head.length > head.tail.len() {
-                let entity = commands
-                    .spawn_bundle(SpriteBundle {
-                        sprite: Sprite {
-                            color: SNAKE_HEAD_COLOR,
-                            ..Default::default()
-                        },
-                        transform: Transform {
-                            translation: Vec3::new(0.0, 0.0, 1.0),
-                            ..Default::default()
-                        },
-                        ..Default::default()
-                    })
-                    .insert(Tail)
-                    .insert(last_position)
-                    .insert(Rec {
-                        width: 1,
-                        height: 1,
-                    })
-                    .insert(CleanUp::new(GameState::MainGameLoop))
-                    .id();
-
-                head.tail.push_front(entity);
+fn track_interpolation(
+    heads: Query<(Entity, &SnakeHead, &Timer)>,
+    mut interpolations: Query<&mut Interpolation>,
+) {
+    for (snake, head, timer) in heads.iter() {
+        let progress = timer.percent();
+        for entity in head.tail.iter().chain(Some(&snake)) {
+            if let Ok(mut interpolation) = interpolations.get_mut(*entity) {
+                interpolation.progress = progress;
             }
         }
     }
 }
 
-fn spawn_apple(mut commands: Commands, food: Query<&Food>) {
-    if food.iter().count() < 1 {
-        let width = ARENA_WIDTH / 2.0;
-        let height = ARENA_HEIGHT / 2.0;
+fn spawn_apple(
+    mut commands: Commands,
+    mut rng: ResMut<GameRng>,
+    theme: Res<ActiveTheme>,
+    arena: Res<ArenaSize>,
+    rules: Res<FoodRules>,
+    food: Query<&Food>,
+) {
+    for _ in food.iter().count()..rules.count {
         commands
             .spawn_bundle(SpriteBundle {
                 sprite: Sprite {
-                    color: FOOD_COLOR,
+                    color: theme.palette.food,
                     ..Default::default()
                 },
                 transform: Transform {
@@ -241,10 +346,9 @@ fn spawn_apple(mut commands: Commands, food: Query<&Food>) {
                 ..Default::default()
             })
             .insert(Food)
-            .insert(Position {
-                x: rand::thread_rng().gen_range(-width..width) as i32,
-                y: rand::thread_rng().gen_range(-height..height) as i32,
-            })
+            .insert(MarkerShape::Diamond)
+            .insert(ThemeColor::Food)
+            .insert(rules::spawn_food(&mut rng.0, *arena))
             .insert(Rec {
                 width: 1,
                 height: 1,
@@ -253,23 +357,10 @@ fn spawn_apple(mut commands: Commands, food: Query<&Food>) {
     }
 }
 
-struct CollectEvent {
-    food: Entity,
-    snake: Entity,
-}
-
-fn collect_food(
-    mut events: EventWriter<CollectEvent>,
-    food: Query<(Entity, &Position), With<Food>>,
-    mut heads: Query<(Entity, &Position), With<SnakeHead>>,
-) {
-    for (food, a) in food.iter() {
-        for (snake, b) in heads.iter_mut() {
-            if a == b {
-                events.send(CollectEvent { food, snake });
-            }
-        }
-    }
+pub struct CollectEvent {
+    pub food: Entity,
+    pub snake: Entity,
+    pub position: Position,
 }
 
 fn despawn_food(mut commands: Commands, mut events: EventReader<CollectEvent>) {
@@ -278,10 +369,14 @@ fn despawn_food(mut commands: Commands, mut events: EventReader<CollectEvent>) {
     }
 }
 
-fn grow_snake(mut events: EventReader<CollectEvent>, mut heads: Query<&mut SnakeHead>) {
+fn track_length(
+    mut stats: ResMut<RunStats>,
+    mut events: EventReader<CollectEvent>,
+    heads: Query<&SnakeHead>,
+) {
     for event in events.iter() {
-        if let Ok(mut snake) = heads.get_mut(event.snake) {
-            snake.length += 1;
+        if let Ok(snake) = heads.get(event.snake) {
+            stats.length = snake.body.length + 1;
         }
     }
 }
@@ -294,18 +389,18 @@ fn track_score(mut events: EventReader<CollectEvent>, mut score: ResMut<Score>)
     }
 }
 
-fn track_experience(mut events: EventReader<CollectEvent>, mut experience: ResMut<Experience>) {
+fn track_experience(
+    mut events: EventReader<CollectEvent>,
+    mut experience: ResMut<Experience>,
+    mut level_ups: EventWriter<LevelUpEvent>,
+) {
     let count = events.iter().count();
 
     if count > 0 {
+        let level = experience.level();
         experience.0 += count;
-    }
-}
-
-fn update_score_board(score: Res<Score>, mut query: Query<&mut Text, With<ScoreBoard>>) {
-    if score.is_changed() {
-        for mut text in query.iter_mut() {
-            text.sections[0].value = score.0.to_string();
+        if experience.level() > level {
+            level_ups.send(LevelUpEvent(experience.level()));
         }
     }
 }
@@ -321,47 +416,29 @@ fn snake_controls(keyboard_input: Res<Input<KeyCode>>, mut q: Query<&mut SnakeHe
         } else if keyboard_input.pressed(KeyCode::Right) {
             Direction::Right
         } else {
-            head.direction
+            head.body.direction
         };
-        if dir != head.direction.opposite() {
-            head.heading = dir;
-        }
+        head.body.steer(dir);
     }
 }
 
-fn kill_snake_outside_arena(
-    mut snakes: Query<(&Position, &mut SnakeHead), With<SnakeHead>>,
-    arenas: Query<(&Position, &Rec), With<Arena>>,
-) {
-    for (snake, mut head) in snakes.iter_mut() {
-        if arenas
-            .iter()
-            .all(|(position, rec)| !rec.contains(*position + *snake))
-        {
-            head.dead = true;
-        }
-    }
-}
-
-fn kill_snake_hitting_tail(
-    mut snakes: Query<(&Position, &mut SnakeHead)>,
-    tails: Query<&Position, With<Tail>>,
-) {
-    for (snake, mut head) in snakes.iter_mut() {
-        if tails.iter().any(|position| position == snake) {
-            head.dead = true;
+fn track_death(mut stats: ResMut<RunStats>, snakes: Query<&SnakeHead>) {
+    for snake in snakes.iter() {
+        if let Some(cause) = snake.body.cause {
+            stats.cause.get_or_insert(cause);
         }
     }
 }
 
 fn game_over(mut app_state: ResMut<State<GameState>>, snakes: Query<&SnakeHead>) {
-    if snakes.iter().all(|x| x.dead) {
-        app_state.overwrite_push(GameState::GameOver).unwrap();
+    if snakes.iter().all(|x| x.body.is_dead()) {
+        app_state.overwrite_push(GameState::Dying).unwrap();
     }
 }
 
-fn pause_game(mut app_state: ResMut<State<GameState>>, inputs: Res<Input<KeyCode>>) {
+fn pause_game(mut app_state: ResMut<State<GameState>>, mut inputs: ResMut<Input<KeyCode>>) {
     if inputs.just_pressed(KeyCode::P) {
+        inputs.reset(KeyCode::P);
         app_state.push(GameState::PauseMenu).unwrap();
     }
 }
@@ -1,62 +1,101 @@
 use bevy::prelude::*;
 
 use crate::{
-    game_state::{CleanUp, GameState},
-    primitives::Rec,
+    daily::DailyChallenge,
+    game_loop::{RunStats, Score},
+    game_state::{clean_up, CleanUp, GameMode, GameState},
+    locale::Message,
+    primitives::menu::{confirmed, Menu, MenuEvent, MenuPlugin, MenuSystem},
+    rules::DeathCause,
 };
 
 pub struct GameOverPlugin;
 
 impl Plugin for GameOverPlugin {
     fn build(&self, app: &mut App) {
-        app.add_system_set(SystemSet::on_enter(GameState::GameOver).with_system(spawn_ui))
-            .add_system_set(SystemSet::on_update(GameState::GameOver).with_system(restart_game));
+        app.add_plugin(MenuPlugin::<GameOverItem>::default())
+            .add_system_set(SystemSet::on_enter(GameState::GameOver).with_system(spawn_ui))
+            .add_system_set(SystemSet::on_resume(GameState::GameOver).with_system(spawn_ui))
+            .add_system_set(
+                SystemSet::on_pause(GameState::GameOver).with_system(clean_up(GameState::GameOver)),
+            )
+            .add_system_set(
+                SystemSet::on_update(GameState::GameOver)
+                    .with_system(select_item.after(MenuSystem::Navigate)),
+            );
     }
 }
 
-fn spawn_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
-    let font = asset_server.load("fonts/FiraSans-Bold.ttf");
-    let text_style = TextStyle {
-        font,
-        font_size: 60.0,
-        color: Color::WHITE,
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum GameOverItem {
+    Retry,
+    Replay,
+    Menu,
+}
+
+fn spawn_ui(mut commands: Commands, score: Res<Score>, stats: Res<RunStats>, mode: Res<GameMode>) {
+    let seconds = stats.time.as_secs_f32();
+    let per_minute = if seconds > 0.0 {
+        score.0 as f32 / seconds * 60.0
+    } else {
+        0.0
     };
-    let text_alignment = TextAlignment {
-        vertical: VerticalAlign::Center,
-        horizontal: HorizontalAlign::Center,
+    let cause = match stats.cause {
+        Some(DeathCause::Wall) => "cause.wall",
+        Some(DeathCause::Tail) => "cause.tail",
+        None => "cause.unknown",
     };
-    commands
-        .spawn_bundle(Text2dBundle {
-            text: Text::with_section("Game Over", text_style, text_alignment),
-            transform: Transform {
-                translation: Vec3::new(0.0, 0.0, 2.1),
-                ..Default::default()
-            },
-            ..Default::default()
-        })
-        .insert(CleanUp::new(GameState::GameOver));
+
+    let mut menu = Menu::new("game_over.title")
+        .item("game_over.retry", GameOverItem::Retry)
+        .item("game_over.replay", GameOverItem::Replay)
+        .item("game_over.menu", GameOverItem::Menu)
+        .with_overlay()
+        .line(Message::count("game_over.score", score.0))
+        .line(Message::count("game_over.length", stats.length))
+        .line(Message::new("game_over.time").arg(
+            "time",
+            format!("{}:{:02}", seconds as u64 / 60, seconds as u64 % 60),
+        ))
+        .line(Message::new("game_over.rate").arg("rate", format!("{:.1}", per_minute)))
+        .line(Message::new("game_over.cause").arg_message("cause", cause.into()));
+
+    match *mode {
+        GameMode::Classic => {
+            menu = menu.line(Message::new("game_over.best").arg("score", stats.best.max(score.0)));
+            if score.0 > stats.best {
+                menu = menu.highlight("game_over.record");
+            }
+        }
+        GameMode::Daily(day) | GameMode::DailyPractice(day) => {
+            menu = menu.line(
+                Message::new("game_over.daily").arg("date", DailyChallenge::for_day(day).date()),
+            );
+        }
+    }
 
     commands
-        .spawn_bundle(SpriteBundle {
-            sprite: Sprite {
-                color: Color::rgba(0.0, 0.0, 0.0, 0.5),
-                ..Default::default()
-            },
-            transform: Transform {
-                translation: Vec3::new(0.0, 0.0, 2.0),
-                ..Default::default()
-            },
-            ..Default::default()
-        })
-        .insert(Rec {
-            width: 10000,
-            height: 10000,
-        })
+        .spawn()
+        .insert(menu)
         .insert(CleanUp::new(GameState::GameOver));
 }
 
-fn restart_game(input: Res<Input<KeyCode>>, mut state: ResMut<State<GameState>>) {
-    if input.just_pressed(KeyCode::Return) {
-        state.replace(GameState::MainGameLoop).unwrap();
+fn select_item(
+    mut events: EventReader<MenuEvent<GameOverItem>>,
+    mut state: ResMut<State<GameState>>,
+    mut mode: ResMut<GameMode>,
+) {
+    let item = match confirmed(&mut events) {
+        Some(item) => item,
+        None => return,
+    };
+
+    match item {
+        GameOverItem::Retry => {
+            *mode = mode.retried();
+            state.replace(GameState::MainGameLoop).unwrap();
+        }
+        GameOverItem::Replay => state.push(GameState::ReplayView).unwrap(),
+        GameOverItem::Menu => state.replace(GameState::StartMenu).unwrap(),
     }
 }
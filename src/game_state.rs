@@ -9,6 +9,31 @@ pub enum GameState {
     MainGameLoop,
     StartMenu,
     PauseMenu,
+    ModeMenu,
+    SettingsMenu,
+    HighScoreMenu,
+    ReplayView,
+    Countdown,
+    Dying,
+}
+
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum GameMode {
+    #[default]
+    Classic,
+    Daily(u64),
+    DailyPractice(u64),
+}
+
+impl GameMode {
+    /// The mode for another run straight after this one. A daily only gets
+    /// one scored attempt, so going again turns into practice.
+    pub fn retried(self) -> Self {
+        match self {
+            GameMode::Daily(day) => GameMode::DailyPractice(day),
+            mode => mode,
+        }
+    }
 }
 
 pub struct GameStatePlugin;
@@ -17,9 +42,20 @@ impl Plugin for GameStatePlugin {
     fn build(&self, app: &mut App) {
         use GameState::*;
 
-        app.add_state(StartMenu);
+        app.add_state(StartMenu).init_resource::<GameMode>();
 
-        let states = [GameOver, MainGameLoop, StartMenu, PauseMenu];
+        let states = [
+            GameOver,
+            MainGameLoop,
+            StartMenu,
+            PauseMenu,
+            ModeMenu,
+            SettingsMenu,
+            HighScoreMenu,
+            ReplayView,
+            Countdown,
+            Dying,
+        ];
 
         for state in states {
             app.add_system_set(SystemSet::on_exit(state).with_system(clean_up(state)));
@@ -41,7 +77,7 @@ where
     }
 }
 
-fn clean_up<T>(state: T) -> impl Fn(Commands, Query<(Entity, &CleanUp<T>)>)
+pub fn clean_up<T>(state: T) -> impl Fn(Commands, Query<(Entity, &CleanUp<T>)>)
 where
     T: Send + Sync + 'static + Clone + Hash + Debug + Eq,
 {
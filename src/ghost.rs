@@ -0,0 +1,125 @@
+use bevy::prelude::*;
+
+use crate::arena::ArenaSize;
+use crate::game_loop::{FoodRules, GameStage, TickEvent};
+use crate::game_state::{CleanUp, GameMode, GameState};
+use crate::primitives::{Position, Rec};
+use crate::replay::{Playback, Replay, Seed};
+use crate::settings::Settings;
+use crate::theme::{ActiveTheme, ThemeColor};
+
+pub struct GhostPlugin;
+
+impl Plugin for GhostPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(
+            SystemSet::on_enter(GameState::MainGameLoop)
+                .with_system(spawn_ghost.after(GameStage::Setup)),
+        )
+        .add_system_set(SystemSet::on_update(GameState::MainGameLoop).with_system(move_ghost));
+    }
+}
+
+#[derive(Component)]
+struct Ghost {
+    replay: Replay,
+    playback: Playback,
+    tail: Vec<Entity>,
+}
+
+#[derive(Component)]
+struct GhostTail;
+
+fn spawn_ghost(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    theme: Res<ActiveTheme>,
+    seed: Res<Seed>,
+    arena: Res<ArenaSize>,
+    rules: Res<FoodRules>,
+    mode: Res<GameMode>,
+) {
+    if !settings.gameplay.ghost {
+        return;
+    }
+
+    if let Some(replay) = Replay::new(seed.0, *arena, *rules, *mode).best() {
+        commands
+            .spawn_bundle(ghost_sprite(&theme))
+            .insert(ThemeColor::Ghost)
+            .insert(Ghost {
+                replay,
+                playback: Playback::default(),
+                tail: Vec::new(),
+            })
+            .insert(Position { x: 0, y: 0 })
+            .insert(Rec {
+                width: 1,
+                height: 1,
+            })
+            .insert(CleanUp::new(GameState::MainGameLoop));
+    }
+}
+
+fn ghost_sprite(theme: &ActiveTheme) -> SpriteBundle {
+    SpriteBundle {
+        sprite: Sprite {
+            color: theme.palette.ghost,
+            ..Default::default()
+        },
+        transform: Transform {
+            translation: Vec3::new(0.0, 0.0, 0.9),
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+fn move_ghost(
+    mut commands: Commands,
+    theme: Res<ActiveTheme>,
+    mut events: EventReader<TickEvent>,
+    mut ghosts: Query<(&mut Ghost, &mut Position)>,
+    mut tails: Query<&mut Position, (With<GhostTail>, Without<Ghost>)>,
+) {
+    for _ in events.iter() {
+        for (mut ghost, mut position) in ghosts.iter_mut() {
+            let Ghost {
+                replay,
+                playback,
+                tail,
+            } = ghost.as_mut();
+
+            if playback.step(replay).is_none() {
+                continue;
+            }
+            *position = playback.head;
+
+            while tail.len() > playback.tail.len() {
+                let entity = tail.pop().unwrap();
+                commands.entity(entity).despawn();
+            }
+
+            for (index, segment) in playback.tail.iter().enumerate() {
+                if let Some(entity) = tail.get(index) {
+                    if let Ok(mut position) = tails.get_mut(*entity) {
+                        *position = *segment;
+                    }
+                } else {
+                    let entity = commands
+                        .spawn_bundle(ghost_sprite(&theme))
+                        .insert(GhostTail)
+                        .insert(ThemeColor::Ghost)
+                        .insert(*segment)
+                        .insert(Rec {
+                            width: 1,
+                            height: 1,
+                        })
+                        .insert(CleanUp::new(GameState::MainGameLoop))
+                        .id();
+                    tail.push(entity);
+                }
+            }
+        }
+    }
+}
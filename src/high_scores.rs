@@ -0,0 +1,119 @@
+use std::fs;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::daily::DailyLeaderboard;
+use crate::game_loop::Score;
+use crate::game_state::{CleanUp, GameMode, GameState};
+use crate::locale::Message;
+use crate::primitives::menu::{confirmed, Menu, MenuEvent, MenuPlugin, MenuSystem};
+
+const HIGH_SCORES_PATH: &str = "scores.ron";
+const MAX_HIGH_SCORES: usize = 10;
+const MAX_DAILY_ENTRIES: usize = 5;
+
+pub struct HighScoresPlugin;
+
+impl Plugin for HighScoresPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugin(MenuPlugin::<HighScoreMenuItem>::default())
+            .insert_resource(HighScores::load())
+            .add_system_set(SystemSet::on_enter(GameState::GameOver).with_system(record_score))
+            .add_system_set(SystemSet::on_enter(GameState::HighScoreMenu).with_system(spawn_ui))
+            .add_system_set(
+                SystemSet::on_update(GameState::HighScoreMenu)
+                    .with_system(select_item.after(MenuSystem::Navigate)),
+            );
+    }
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct HighScores {
+    pub classic: Vec<usize>,
+}
+
+impl HighScores {
+    fn load() -> Self {
+        fs::read_to_string(HIGH_SCORES_PATH)
+            .ok()
+            .and_then(|contents| ron::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self) {
+        let result = ron::to_string(self)
+            .map_err(|e| e.to_string())
+            .and_then(|contents| fs::write(HIGH_SCORES_PATH, contents).map_err(|e| e.to_string()));
+
+        if let Err(error) = result {
+            error!("failed to save high scores: {}", error);
+        }
+    }
+
+    pub fn best(&self) -> usize {
+        self.classic.first().copied().unwrap_or(0)
+    }
+
+    fn insert(&mut self, score: usize) {
+        let index = self.classic.partition_point(|x| *x >= score);
+        self.classic.insert(index, score);
+        self.classic.truncate(MAX_HIGH_SCORES);
+    }
+}
+
+fn record_score(score: Res<Score>, mode: Res<GameMode>, mut high_scores: ResMut<HighScores>) {
+    if *mode == GameMode::Classic && score.0 > 0 {
+        high_scores.insert(score.0);
+        high_scores.save();
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HighScoreMenuItem {
+    Back,
+}
+
+fn spawn_ui(
+    mut commands: Commands,
+    high_scores: Res<HighScores>,
+    leaderboard: Res<DailyLeaderboard>,
+) {
+    let mut menu = Menu::new("scores.title")
+        .item("common.back", HighScoreMenuItem::Back)
+        .on_back(HighScoreMenuItem::Back);
+
+    for (index, score) in high_scores.classic.iter().enumerate() {
+        menu = menu.line(
+            Message::new("scores.entry")
+                .arg("rank", index + 1)
+                .arg("score", score),
+        );
+    }
+    if high_scores.classic.is_empty() {
+        menu = menu.line("scores.empty");
+    }
+
+    menu = menu.line(Message::raw("")).line("scores.daily");
+    for entry in leaderboard.entries.iter().rev().take(MAX_DAILY_ENTRIES) {
+        menu = menu.line(
+            Message::new("scores.daily_entry")
+                .arg("date", &entry.date)
+                .arg("score", entry.score),
+        );
+    }
+
+    commands
+        .spawn()
+        .insert(menu)
+        .insert(CleanUp::new(GameState::HighScoreMenu));
+}
+
+fn select_item(
+    mut events: EventReader<MenuEvent<HighScoreMenuItem>>,
+    mut state: ResMut<State<GameState>>,
+) {
+    if let Some(HighScoreMenuItem::Back) = confirmed(&mut events) {
+        state.replace(GameState::StartMenu).unwrap();
+    }
+}
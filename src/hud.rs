@@ -0,0 +1,209 @@
+use bevy::prelude::*;
+
+use crate::experience::{Experience, ExperienceBar};
+use crate::game_loop::{RunStats, Score};
+use crate::game_state::{CleanUp, GameState};
+use crate::layout::{Layout, LayoutSystem};
+use crate::locale::{Localization, Message};
+use crate::settings::Settings;
+use crate::theme::{ActiveTheme, ThemeColor, ThemeSystem};
+
+pub const HUD_HEIGHT: f32 = 40.0;
+const EXPERIENCE_BAR_HEIGHT: f32 = 12.0;
+
+pub struct HudPlugin;
+
+impl Plugin for HudPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(
+            SystemSet::on_enter(GameState::MainGameLoop)
+                .with_system(spawn_hud)
+                .with_system(spawn_experience_bar),
+        )
+        .add_system(update_hud)
+        .add_system(
+            resize_hud
+                .after(LayoutSystem::Update)
+                .after(ThemeSystem::Activate),
+        );
+    }
+}
+
+#[derive(Component)]
+struct Hud;
+
+#[derive(Component)]
+struct HudExperienceBar;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Component)]
+enum HudField {
+    Score,
+    Best,
+    Length,
+    Level,
+    Speed,
+    Time,
+}
+
+const FIELDS: [HudField; 6] = [
+    HudField::Score,
+    HudField::Best,
+    HudField::Length,
+    HudField::Level,
+    HudField::Speed,
+    HudField::Time,
+];
+
+impl HudField {
+    fn value(
+        self,
+        score: &Score,
+        stats: &RunStats,
+        experience: &Experience,
+        settings: &Settings,
+    ) -> Message {
+        match self {
+            Self::Score => Message::new("hud.score").arg("score", score.0),
+            Self::Best => Message::new("hud.best").arg("score", stats.best.max(score.0)),
+            Self::Length => Message::new("hud.length").arg("length", stats.length),
+            Self::Level => Message::new("hud.level").arg("level", experience.level()),
+            Self::Speed => Message::new("hud.speed").arg(
+                "speed",
+                format!("{:.1}", 1000.0 / settings.gameplay.tick_millis as f32),
+            ),
+            Self::Time => {
+                let seconds = stats.time.as_secs();
+                Message::new("hud.time")
+                    .arg("time", format!("{}:{:02}", seconds / 60, seconds % 60))
+            }
+        }
+    }
+}
+
+fn hud_style(layout: &Layout) -> Style {
+    let margin = layout.margin();
+
+    Style {
+        position_type: PositionType::Absolute,
+        position: Rect {
+            top: Val::Px(margin.y),
+            left: Val::Px(margin.x),
+            ..Default::default()
+        },
+        size: Size::new(
+            Val::Px(layout.viewport.x),
+            Val::Px(HUD_HEIGHT * layout.scale),
+        ),
+        justify_content: JustifyContent::SpaceAround,
+        align_items: AlignItems::Center,
+        ..Default::default()
+    }
+}
+
+fn experience_bar_style(layout: &Layout) -> Style {
+    let margin = layout.margin();
+
+    Style {
+        position_type: PositionType::Absolute,
+        position: Rect {
+            bottom: Val::Px(margin.y),
+            left: Val::Px(margin.x),
+            ..Default::default()
+        },
+        size: Size::new(
+            Val::Px(layout.viewport.x),
+            Val::Px(EXPERIENCE_BAR_HEIGHT * layout.scale),
+        ),
+        ..Default::default()
+    }
+}
+
+fn spawn_hud(mut commands: Commands, theme: Res<ActiveTheme>, layout: Res<Layout>) {
+    let text_style = TextStyle {
+        font: theme.font.clone(),
+        font_size: theme.sizes.hud * layout.scale,
+        color: theme.palette.text,
+    };
+
+    commands
+        .spawn_bundle(NodeBundle {
+            style: hud_style(&layout),
+            color: Color::NONE.into(),
+            ..Default::default()
+        })
+        .insert(Hud)
+        .insert(CleanUp::new(GameState::MainGameLoop))
+        .with_children(|parent| {
+            for field in FIELDS {
+                parent
+                    .spawn_bundle(TextBundle {
+                        text: Text::with_section("", text_style.clone(), Default::default()),
+                        ..Default::default()
+                    })
+                    .insert(field)
+                    .insert(ThemeColor::Text);
+            }
+        });
+}
+
+fn spawn_experience_bar(mut commands: Commands, theme: Res<ActiveTheme>, layout: Res<Layout>) {
+    commands
+        .spawn_bundle(NodeBundle {
+            style: experience_bar_style(&layout),
+            color: Color::NONE.into(),
+            ..Default::default()
+        })
+        .insert(HudExperienceBar)
+        .insert(CleanUp::new(GameState::MainGameLoop))
+        .with_children(|parent| {
+            parent
+                .spawn_bundle(NodeBundle {
+                    style: Style {
+                        size: Size::new(Val::Percent(0.0), Val::Percent(100.0)),
+                        ..Default::default()
+                    },
+                    color: theme.palette.experience_bar.into(),
+                    ..Default::default()
+                })
+                .insert(ExperienceBar)
+                .insert(ThemeColor::ExperienceBar);
+        });
+}
+
+fn update_hud(
+    score: Res<Score>,
+    stats: Res<RunStats>,
+    experience: Res<Experience>,
+    settings: Res<Settings>,
+    localization: Res<Localization>,
+    mut fields: Query<(&HudField, &mut Text)>,
+) {
+    for (field, mut text) in fields.iter_mut() {
+        let value = localization.resolve(&field.value(&score, &stats, &experience, &settings));
+        if text.sections[0].value != value {
+            text.sections[0].value = value;
+        }
+    }
+}
+
+fn resize_hud(
+    layout: Res<Layout>,
+    theme: Res<ActiveTheme>,
+    mut huds: Query<&mut Style, (With<Hud>, Without<HudExperienceBar>)>,
+    mut bars: Query<&mut Style, (With<HudExperienceBar>, Without<Hud>)>,
+    mut fields: Query<&mut Text, With<HudField>>,
+) {
+    if !layout.is_changed() && !theme.is_changed() {
+        return;
+    }
+
+    for mut style in huds.iter_mut() {
+        *style = hud_style(&layout);
+    }
+    for mut style in bars.iter_mut() {
+        *style = experience_bar_style(&layout);
+    }
+    for mut text in fields.iter_mut() {
+        text.sections[0].style.font_size = theme.sizes.hud * layout.scale;
+    }
+}
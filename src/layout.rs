@@ -0,0 +1,164 @@
+use bevy::prelude::*;
+use bevy::window::WindowResized;
+
+use crate::settings::Settings;
+
+pub const REFERENCE_WIDTH: f32 = 1280.0;
+pub const REFERENCE_HEIGHT: f32 = 720.0;
+
+pub struct LayoutPlugin;
+
+impl Plugin for LayoutPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Layout>()
+            .add_startup_system(spawn_letterbox)
+            .add_system(update_layout.label(LayoutSystem::Update))
+            .add_system(scale_anchored.after(LayoutSystem::Update))
+            .add_system(resize_letterbox.after(LayoutSystem::Update));
+    }
+}
+
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, SystemLabel)]
+pub enum LayoutSystem {
+    Update,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Layout {
+    pub window: Vec2,
+    pub viewport: Vec2,
+    pub scale: f32,
+}
+
+impl Layout {
+    fn new(window: Vec2, letterbox: bool) -> Self {
+        let scale = f32::min(window.x / REFERENCE_WIDTH, window.y / REFERENCE_HEIGHT);
+        let viewport = if letterbox {
+            Vec2::new(REFERENCE_WIDTH, REFERENCE_HEIGHT) * scale
+        } else {
+            window
+        };
+
+        Layout {
+            window,
+            viewport,
+            scale,
+        }
+    }
+
+    pub fn margin(&self) -> Vec2 {
+        (self.window - self.viewport) / 2.0
+    }
+}
+
+#[derive(Component)]
+pub struct LayoutScaled;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Component)]
+enum Letterbox {
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+fn update_layout(
+    mut resized: EventReader<WindowResized>,
+    windows: Res<Windows>,
+    settings: Res<Settings>,
+    mut layout: ResMut<Layout>,
+) {
+    let resized = resized.iter().count() > 0;
+    if !resized && !settings.is_changed() && layout.scale > 0.0 {
+        return;
+    }
+
+    if let Some(window) = windows.get_primary() {
+        let new = Layout::new(
+            Vec2::new(window.width(), window.height()),
+            settings.video.letterbox,
+        );
+        if *layout != new {
+            *layout = new;
+        }
+    }
+}
+
+fn scale_anchored(
+    layout: Res<Layout>,
+    mut anchored: Query<(&mut Transform, ChangeTrackers<LayoutScaled>)>,
+) {
+    for (mut transform, tracker) in anchored.iter_mut() {
+        if layout.is_changed() || tracker.is_added() {
+            transform.scale = Vec3::splat(layout.scale);
+        }
+    }
+}
+
+fn spawn_letterbox(mut commands: Commands) {
+    for side in [
+        Letterbox::Left,
+        Letterbox::Right,
+        Letterbox::Top,
+        Letterbox::Bottom,
+    ] {
+        commands
+            .spawn_bundle(NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    size: Size::new(Val::Px(0.0), Val::Px(0.0)),
+                    ..Default::default()
+                },
+                color: Color::BLACK.into(),
+                ..Default::default()
+            })
+            .insert(side);
+    }
+}
+
+fn resize_letterbox(layout: Res<Layout>, mut bars: Query<(&Letterbox, &mut Style)>) {
+    if !layout.is_changed() {
+        return;
+    }
+
+    let margin = layout.margin();
+    for (side, mut style) in bars.iter_mut() {
+        let (position, size) = match side {
+            Letterbox::Left => (
+                Rect {
+                    left: Val::Px(0.0),
+                    top: Val::Px(0.0),
+                    ..Default::default()
+                },
+                Size::new(Val::Px(margin.x), Val::Percent(100.0)),
+            ),
+            Letterbox::Right => (
+                Rect {
+                    right: Val::Px(0.0),
+                    top: Val::Px(0.0),
+                    ..Default::default()
+                },
+                Size::new(Val::Px(margin.x), Val::Percent(100.0)),
+            ),
+            Letterbox::Top => (
+                Rect {
+                    left: Val::Px(0.0),
+                    top: Val::Px(0.0),
+                    ..Default::default()
+                },
+                Size::new(Val::Percent(100.0), Val::Px(margin.y)),
+            ),
+            Letterbox::Bottom => (
+                Rect {
+                    left: Val::Px(0.0),
+                    bottom: Val::Px(0.0),
+                    ..Default::default()
+                },
+                Size::new(Val::Percent(100.0), Val::Px(margin.y)),
+            ),
+        };
+
+        style.position = position;
+        style.size = size;
+    }
+}
@@ -1,29 +1,79 @@
 use bevy::prelude::*;
 
 mod arena;
+mod audio_cues;
+mod camera;
+mod config;
+mod countdown;
+mod daily;
+mod dying;
 mod experience;
+pub mod export;
 mod game_loop;
 mod game_over;
 mod game_state;
+mod ghost;
+mod high_scores;
+mod hud;
+mod layout;
+mod locale;
+mod markers;
+mod mode_menu;
+mod particles;
 mod pause_menu;
 mod primitives;
+pub mod replay;
+mod replay_view;
+pub mod rules;
+pub mod settings;
+mod settings_menu;
+mod snake_sprites;
+mod sound;
 mod start_menu;
+pub mod theme;
 
 pub fn run() {
     App::new()
-        .add_plugins(DefaultPlugins)
+        .add_plugin(settings::SettingsPlugin)
+        .add_plugins_with(DefaultPlugins, |group| {
+            group.disable::<bevy::audio::AudioPlugin>()
+        })
+        .add_plugin(theme::ThemePlugin)
+        .add_plugin(locale::LocalePlugin)
+        .add_plugin(layout::LayoutPlugin)
         .add_plugin(primitives::PrimitivesPlugin)
+        .add_plugin(arena::ArenaPlugin)
+        .add_plugin(markers::MarkersPlugin)
         .add_plugin(game_loop::GameLoopPlugin)
+        .add_plugin(snake_sprites::SnakeSpritesPlugin)
+        .add_plugin(particles::ParticlesPlugin)
+        .add_plugin(dying::DyingPlugin)
+        .add_plugin(camera::CameraPlugin)
+        .add_plugin(sound::SoundPlugin)
+        .add_plugin(audio_cues::AudioCuesPlugin)
         .add_plugin(game_over::GameOverPlugin)
         .add_plugin(game_state::GameStatePlugin)
         .add_plugin(start_menu::StartMenuPlugin)
         .add_plugin(pause_menu::PauseMenuPlugin)
         .add_plugin(experience::PauseMenuPlugin)
+        .add_plugin(replay::ReplayPlugin)
+        .add_plugin(ghost::GhostPlugin)
+        .add_plugin(daily::DailyPlugin)
+        .add_plugin(config::ConfigPlugin)
+        .add_plugin(mode_menu::ModeMenuPlugin)
+        .add_plugin(settings_menu::SettingsMenuPlugin)
+        .add_plugin(high_scores::HighScoresPlugin)
+        .add_plugin(replay_view::ReplayViewPlugin)
+        .add_plugin(hud::HudPlugin)
+        .add_plugin(countdown::CountdownPlugin)
         .add_system(bevy::input::system::exit_on_esc_system)
         .add_startup_system(setup_camera)
         .run();
 }
 
 fn setup_camera(mut commands: Commands) {
-    commands.spawn_bundle(OrthographicCameraBundle::new_2d());
+    commands
+        .spawn_bundle(OrthographicCameraBundle::new_2d())
+        .insert(camera::GameCamera);
+    commands.spawn_bundle(UiCameraBundle::default());
 }
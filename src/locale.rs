@@ -0,0 +1,247 @@
+use std::collections::HashMap;
+
+use bevy::asset::{AssetLoader, LoadContext, LoadedAsset};
+use bevy::prelude::*;
+use bevy::reflect::TypeUuid;
+use bevy::utils::BoxedFuture;
+use serde::Deserialize;
+
+use crate::settings::Settings;
+
+pub const LANGUAGES: [(&str, &str); 2] = [("en", "English"), ("de", "Deutsch")];
+
+const FALLBACK_TABLE: &str = include_str!("../assets/locales/en.locale.ron");
+
+pub struct LocalePlugin;
+
+impl Plugin for LocalePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<StringTable>()
+            .init_asset_loader::<StringTableLoader>()
+            .init_resource::<Localization>()
+            .add_system(switch_language.label(LocaleSystem::Switch))
+            .add_system(
+                activate_language
+                    .label(LocaleSystem::Activate)
+                    .after(LocaleSystem::Switch),
+            )
+            .add_system(update_localized_texts.after(LocaleSystem::Activate));
+    }
+}
+
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, SystemLabel)]
+pub enum LocaleSystem {
+    Switch,
+    Activate,
+}
+
+#[derive(Debug, Default, Clone, Deserialize, TypeUuid)]
+#[uuid = "2f71c0a9-84d5-4e0b-b6a3-5c19e7d48f20"]
+#[serde(default)]
+pub struct StringTable {
+    strings: HashMap<String, String>,
+    plurals: HashMap<String, Plural>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Plural {
+    one: String,
+    other: String,
+}
+
+impl StringTable {
+    pub fn path(language: &str) -> String {
+        format!("locales/{}.locale.ron", language)
+    }
+
+    fn get(&self, key: &str, count: Option<usize>) -> Option<&String> {
+        let plural = count.and_then(|count| {
+            self.plurals.get(key).map(|plural| {
+                if count == 1 {
+                    &plural.one
+                } else {
+                    &plural.other
+                }
+            })
+        });
+
+        plural.or_else(|| self.strings.get(key))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum MessageText {
+    Key(&'static str),
+    Count(&'static str, usize),
+    Raw(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Message {
+    text: MessageText,
+    args: Vec<(&'static str, Message)>,
+}
+
+impl Message {
+    pub fn new(key: &'static str) -> Self {
+        Message {
+            text: MessageText::Key(key),
+            args: Vec::new(),
+        }
+    }
+
+    pub fn count(key: &'static str, count: usize) -> Self {
+        Message {
+            text: MessageText::Count(key, count),
+            args: Vec::new(),
+        }
+    }
+
+    pub fn raw(text: impl ToString) -> Self {
+        Message {
+            text: MessageText::Raw(text.to_string()),
+            args: Vec::new(),
+        }
+    }
+
+    pub fn arg(self, name: &'static str, value: impl ToString) -> Self {
+        self.arg_message(name, Message::raw(value))
+    }
+
+    pub fn arg_message(mut self, name: &'static str, value: Message) -> Self {
+        self.args.push((name, value));
+        self
+    }
+}
+
+impl From<&'static str> for Message {
+    fn from(key: &'static str) -> Self {
+        Message::new(key)
+    }
+}
+
+pub struct Localization {
+    pub language: String,
+    table: StringTable,
+    fallback: StringTable,
+    handle: Handle<StringTable>,
+}
+
+impl Localization {
+    pub fn resolve(&self, message: &Message) -> String {
+        let mut text = match &message.text {
+            MessageText::Raw(text) => text.clone(),
+            MessageText::Key(key) => self.lookup(key, None),
+            MessageText::Count(key, count) => self
+                .lookup(key, Some(*count))
+                .replace("{n}", &count.to_string()),
+        };
+
+        for (name, value) in &message.args {
+            text = text.replace(&format!("{{{}}}", name), &self.resolve(value));
+        }
+
+        text
+    }
+
+    fn lookup(&self, key: &str, count: Option<usize>) -> String {
+        self.table
+            .get(key, count)
+            .or_else(|| self.fallback.get(key, count))
+            .cloned()
+            .unwrap_or_else(|| key.to_string())
+    }
+}
+
+impl FromWorld for Localization {
+    fn from_world(world: &mut World) -> Self {
+        let language = world
+            .get_resource::<Settings>()
+            .map(|settings| settings.language.clone())
+            .unwrap_or_default();
+        let asset_server = world.get_resource::<AssetServer>().unwrap();
+        let fallback = ron::from_str(FALLBACK_TABLE).unwrap_or_else(|error| {
+            error!("could not parse the built-in string table: {}", error);
+            StringTable::default()
+        });
+
+        Localization {
+            handle: asset_server.load(StringTable::path(&language).as_str()),
+            table: StringTable::default(),
+            fallback,
+            language,
+        }
+    }
+}
+
+#[derive(Default)]
+struct StringTableLoader;
+
+impl AssetLoader for StringTableLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+        Box::pin(async move {
+            let table = ron::de::from_bytes::<StringTable>(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(table));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["locale.ron"]
+    }
+}
+
+#[derive(Component)]
+pub struct LocalizedText(pub Message);
+
+fn switch_language(
+    settings: Res<Settings>,
+    asset_server: Res<AssetServer>,
+    tables: Res<Assets<StringTable>>,
+    mut localization: ResMut<Localization>,
+) {
+    if settings.is_changed() && settings.language != localization.language {
+        localization.language = settings.language.clone();
+        localization.handle = asset_server.load(StringTable::path(&localization.language).as_str());
+
+        if let Some(table) = tables.get(&localization.handle) {
+            localization.table = table.clone();
+        }
+    }
+}
+
+fn activate_language(
+    mut events: EventReader<AssetEvent<StringTable>>,
+    tables: Res<Assets<StringTable>>,
+    mut localization: ResMut<Localization>,
+) {
+    for event in events.iter() {
+        let loaded = match event {
+            AssetEvent::Created { handle } | AssetEvent::Modified { handle } => handle,
+            AssetEvent::Removed { .. } => continue,
+        };
+
+        if *loaded != localization.handle {
+            continue;
+        }
+
+        if let Some(table) = tables.get(loaded) {
+            localization.table = table.clone();
+        }
+    }
+}
+
+fn update_localized_texts(
+    localization: Res<Localization>,
+    mut texts: Query<(&LocalizedText, &mut Text, ChangeTrackers<LocalizedText>)>,
+) {
+    for (localized, mut text, tracker) in texts.iter_mut() {
+        if localization.is_changed() || tracker.is_changed() {
+            text.sections[0].value = localization.resolve(&localized.0);
+        }
+    }
+}
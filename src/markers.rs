@@ -0,0 +1,84 @@
+use std::f32::consts::FRAC_PI_4;
+
+use bevy::prelude::*;
+
+use crate::settings::Settings;
+use crate::theme::{ActiveTheme, ThemeColor};
+
+pub struct MarkersPlugin;
+
+impl Plugin for MarkersPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(update_markers);
+    }
+}
+
+/// Draws a shape on top of an entity when shape markers are enabled, so it can
+/// be told apart without relying on colour.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Component)]
+pub enum MarkerShape {
+    Diamond,
+    Dot,
+}
+
+impl MarkerShape {
+    fn transform(self) -> Transform {
+        let (size, rotation) = match self {
+            Self::Diamond => (0.45, Quat::from_rotation_z(FRAC_PI_4)),
+            Self::Dot => (0.3, Quat::IDENTITY),
+        };
+
+        Transform {
+            translation: Vec3::new(0.0, 0.0, 0.05),
+            rotation,
+            scale: Vec3::new(size, size, 1.0),
+        }
+    }
+}
+
+#[derive(Component)]
+struct Marker;
+
+fn update_markers(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    theme: Res<ActiveTheme>,
+    shapes: Query<(
+        Entity,
+        &MarkerShape,
+        Option<&Children>,
+        ChangeTrackers<MarkerShape>,
+    )>,
+    markers: Query<(), With<Marker>>,
+) {
+    for (entity, shape, children, tracker) in shapes.iter() {
+        if !tracker.is_added() && !settings.is_changed() {
+            continue;
+        }
+
+        let marked = children
+            .iter()
+            .flat_map(|children| children.iter())
+            .find(|child| markers.get(**child).is_ok());
+
+        match (marked, settings.video.shape_markers) {
+            (Some(marker), false) => commands.entity(*marker).despawn_recursive(),
+            (None, true) => {
+                commands.entity(entity).with_children(|parent| {
+                    parent
+                        .spawn_bundle(SpriteBundle {
+                            sprite: Sprite {
+                                color: theme.palette.marker,
+                                ..Default::default()
+                            },
+                            transform: shape.transform(),
+                            ..Default::default()
+                        })
+                        .insert(Marker)
+                        .insert(ThemeColor::Marker);
+                });
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use bevy::prelude::*;
+
+use crate::daily::{DailyChallenge, DailyLeaderboard};
+use crate::game_state::{CleanUp, GameMode, GameState};
+use crate::locale::Message;
+use crate::primitives::menu::{confirmed, Menu, MenuEvent, MenuPlugin, MenuSystem};
+
+pub struct ModeMenuPlugin;
+
+impl Plugin for ModeMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugin(MenuPlugin::<ModeMenuItem>::default())
+            .add_system_set(SystemSet::on_enter(GameState::ModeMenu).with_system(spawn_ui))
+            .add_system_set(
+                SystemSet::on_update(GameState::ModeMenu)
+                    .with_system(select_item.after(MenuSystem::Navigate)),
+            );
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ModeMenuItem {
+    Classic,
+    Daily,
+    Back,
+}
+
+fn spawn_ui(mut commands: Commands, leaderboard: Res<DailyLeaderboard>) {
+    let challenge = DailyChallenge::today();
+    let played = leaderboard.entry(challenge.day);
+    let daily = match played {
+        Some(entry) => Message::new("modes.daily_played")
+            .arg("date", &entry.date)
+            .arg("score", entry.score),
+        None => Message::new("modes.daily").arg("date", challenge.date()),
+    };
+
+    let mut menu = Menu::new("modes.title")
+        .item("modes.classic", ModeMenuItem::Classic)
+        .item(daily, ModeMenuItem::Daily)
+        .item("common.back", ModeMenuItem::Back)
+        .on_back(ModeMenuItem::Back);
+    if played.is_some() {
+        menu = menu.highlight("modes.daily_done");
+    }
+
+    commands
+        .spawn()
+        .insert(menu)
+        .insert(CleanUp::new(GameState::ModeMenu));
+}
+
+fn select_item(
+    mut events: EventReader<MenuEvent<ModeMenuItem>>,
+    mut state: ResMut<State<GameState>>,
+    mut mode: ResMut<GameMode>,
+    leaderboard: Res<DailyLeaderboard>,
+) {
+    let item = match confirmed(&mut events) {
+        Some(item) => item,
+        None => return,
+    };
+
+    match item {
+        ModeMenuItem::Classic => {
+            *mode = GameMode::Classic;
+            state.replace(GameState::MainGameLoop).unwrap();
+        }
+        ModeMenuItem::Daily => {
+            let challenge = DailyChallenge::today();
+            if leaderboard.entry(challenge.day).is_none() {
+                *mode = GameMode::Daily(challenge.day);
+                state.replace(GameState::MainGameLoop).unwrap();
+            }
+        }
+        ModeMenuItem::Back => state.replace(GameState::StartMenu).unwrap(),
+    }
+}
@@ -0,0 +1,200 @@
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::dying::CollapseEvent;
+use crate::game_loop::{CollectEvent, GameStage, TickEvent};
+use crate::game_state::{CleanUp, GameState};
+use crate::primitives::{Position, Scaling};
+use crate::settings::Settings;
+use crate::theme::ActiveTheme;
+
+const BURST_COUNT: f32 = 16.0;
+const TRAIL_COUNT: f32 = 2.0;
+const DEBRIS_COUNT: f32 = 6.0;
+
+pub struct ParticlesPlugin;
+
+impl Plugin for ParticlesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(
+            SystemSet::on_update(GameState::MainGameLoop)
+                .with_system(spawn_bursts.after(GameStage::Collection))
+                .with_system(spawn_trails.after(GameStage::Movement)),
+        )
+        .add_system_set(SystemSet::on_update(GameState::Dying).with_system(spawn_debris))
+        .add_system(update_particles);
+    }
+}
+
+/// A short-lived square that drifts in cell units and fades out over its
+/// lifetime. Particles are purely cosmetic and never use the game's RNG, so
+/// replays and daily runs are unaffected by them.
+#[derive(Component)]
+struct Particle {
+    position: Vec2,
+    velocity: Vec2,
+    drag: f32,
+    size: f32,
+    color: Color,
+    lifetime: Timer,
+}
+
+struct Emitter {
+    speed: (f32, f32),
+    lifetime: (f32, f32),
+    size: (f32, f32),
+    drag: f32,
+    z: f32,
+}
+
+const BURST: Emitter = Emitter {
+    speed: (2.0, 6.0),
+    lifetime: (0.3, 0.6),
+    size: (0.15, 0.3),
+    drag: 4.0,
+    z: 1.8,
+};
+
+const TRAIL: Emitter = Emitter {
+    speed: (0.1, 0.4),
+    lifetime: (0.2, 0.4),
+    size: (0.2, 0.35),
+    drag: 1.0,
+    z: 0.5,
+};
+
+const DEBRIS: Emitter = Emitter {
+    speed: (1.0, 4.0),
+    lifetime: (0.6, 1.2),
+    size: (0.2, 0.4),
+    drag: 2.0,
+    z: 1.8,
+};
+
+impl Emitter {
+    fn emit(&self, commands: &mut Commands, origin: Vec2, color: Color, count: f32) {
+        let mut rng = rand::thread_rng();
+
+        for _ in 0..count.round() as usize {
+            let angle = rng.gen_range(0.0..TAU);
+            let speed = rng.gen_range(self.speed.0..self.speed.1);
+            let particle = Particle {
+                position: origin,
+                velocity: Vec2::new(angle.cos(), angle.sin()) * speed,
+                drag: self.drag,
+                size: rng.gen_range(self.size.0..self.size.1),
+                color,
+                lifetime: Timer::from_seconds(
+                    rng.gen_range(self.lifetime.0..self.lifetime.1),
+                    false,
+                ),
+            };
+
+            commands
+                .spawn_bundle(SpriteBundle {
+                    sprite: Sprite {
+                        color,
+                        ..Default::default()
+                    },
+                    transform: Transform {
+                        translation: Vec3::new(0.0, 0.0, self.z),
+                        scale: Vec3::ZERO,
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                })
+                .insert(particle)
+                .insert(CleanUp::new(GameState::MainGameLoop));
+        }
+    }
+}
+
+fn cell(position: Position) -> Vec2 {
+    Vec2::new(position.x as f32, position.y as f32)
+}
+
+fn spawn_bursts(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    theme: Res<ActiveTheme>,
+    mut events: EventReader<CollectEvent>,
+) {
+    for event in events.iter() {
+        if settings.video.particles {
+            let count = BURST_COUNT * settings.video.particle_amount;
+            BURST.emit(
+                &mut commands,
+                cell(event.position),
+                theme.palette.food,
+                count,
+            );
+        }
+    }
+}
+
+fn spawn_trails(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    theme: Res<ActiveTheme>,
+    mut events: EventReader<TickEvent>,
+) {
+    for event in events.iter() {
+        if settings.video.particles {
+            let behind = event.position - Position::from(event.heading);
+            let mut color = theme.palette.snake;
+            color.set_a(color.a() * 0.5);
+            let count = TRAIL_COUNT * settings.video.particle_amount;
+            TRAIL.emit(&mut commands, cell(behind), color, count);
+        }
+    }
+}
+
+fn spawn_debris(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    theme: Res<ActiveTheme>,
+    mut events: EventReader<CollapseEvent>,
+) {
+    for event in events.iter() {
+        if settings.video.particles {
+            let count = DEBRIS_COUNT * settings.video.particle_amount;
+            DEBRIS.emit(
+                &mut commands,
+                cell(event.position),
+                theme.palette.snake,
+                count,
+            );
+        }
+    }
+}
+
+fn update_particles(
+    mut commands: Commands,
+    time: Res<Time>,
+    scaling: Res<Scaling>,
+    mut particles: Query<(Entity, &mut Particle, &mut Sprite, &mut Transform)>,
+) {
+    let delta = time.delta_seconds();
+
+    for (entity, mut particle, mut sprite, mut transform) in particles.iter_mut() {
+        if particle.lifetime.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        let velocity = particle.velocity;
+        let drag = particle.drag;
+        particle.position += velocity * delta;
+        particle.velocity *= (1.0 - drag * delta).max(0.0);
+
+        let remaining = 1.0 - particle.lifetime.percent();
+        sprite.color.set_a(particle.color.a() * remaining);
+
+        let size = particle.size * scaling.0 * (0.5 + remaining / 2.0);
+        transform.translation.x = particle.position.x * scaling.0;
+        transform.translation.y = particle.position.y * scaling.0;
+        transform.scale = Vec3::new(size, size, 1.0);
+    }
+}
@@ -1,62 +1,73 @@
 use bevy::prelude::*;
 
 use crate::{
-    game_state::{CleanUp, GameState},
-    primitives::Rec,
+    countdown::resume_game,
+    game_state::{clean_up, CleanUp, GameMode, GameState},
+    primitives::menu::{confirmed, Menu, MenuEvent, MenuPlugin, MenuSystem},
+    settings::Settings,
 };
 
 pub struct PauseMenuPlugin;
 
 impl Plugin for PauseMenuPlugin {
     fn build(&self, app: &mut App) {
-        app.add_system_set(SystemSet::on_enter(GameState::PauseMenu).with_system(spawn_ui))
-            .add_system_set(SystemSet::on_update(GameState::PauseMenu).with_system(resume));
+        app.add_plugin(MenuPlugin::<PauseMenuItem>::default())
+            .add_system_set(SystemSet::on_enter(GameState::PauseMenu).with_system(spawn_ui))
+            .add_system_set(SystemSet::on_resume(GameState::PauseMenu).with_system(spawn_ui))
+            .add_system_set(
+                SystemSet::on_pause(GameState::PauseMenu)
+                    .with_system(clean_up(GameState::PauseMenu)),
+            )
+            .add_system_set(
+                SystemSet::on_update(GameState::PauseMenu)
+                    .with_system(select_item.after(MenuSystem::Navigate)),
+            );
     }
 }
 
-fn spawn_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
-    let font = asset_server.load("fonts/FiraSans-Bold.ttf");
-    let text_style = TextStyle {
-        font,
-        font_size: 60.0,
-        color: Color::WHITE,
-    };
-    let text_alignment = TextAlignment {
-        vertical: VerticalAlign::Center,
-        horizontal: HorizontalAlign::Center,
-    };
-    commands
-        .spawn_bundle(Text2dBundle {
-            text: Text::with_section("Paused", text_style, text_alignment),
-            transform: Transform {
-                translation: Vec3::new(0.0, 0.0, 2.1),
-                ..Default::default()
-            },
-            ..Default::default()
-        })
-        .insert(CleanUp::new(GameState::PauseMenu));
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PauseMenuItem {
+    Resume,
+    Restart,
+    Settings,
+    Quit,
+}
 
+fn spawn_ui(mut commands: Commands) {
     commands
-        .spawn_bundle(SpriteBundle {
-            sprite: Sprite {
-                color: Color::rgba(0.0, 0.0, 0.0, 0.5),
-                ..Default::default()
-            },
-            transform: Transform {
-                translation: Vec3::new(0.0, 0.0, 2.0),
-                ..Default::default()
-            },
-            ..Default::default()
-        })
-        .insert(Rec {
-            width: 10000,
-            height: 10000,
-        })
+        .spawn()
+        .insert(
+            Menu::new("pause.title")
+                .item("pause.resume", PauseMenuItem::Resume)
+                .item("pause.restart", PauseMenuItem::Restart)
+                .item("pause.settings", PauseMenuItem::Settings)
+                .item("pause.quit", PauseMenuItem::Quit)
+                .on_back(PauseMenuItem::Resume)
+                .with_overlay(),
+        )
         .insert(CleanUp::new(GameState::PauseMenu));
 }
 
-fn resume(input: Res<Input<KeyCode>>, mut state: ResMut<State<GameState>>) {
-    if input.just_pressed(KeyCode::Return) {
-        state.pop().unwrap();
+fn select_item(
+    mut events: EventReader<MenuEvent<PauseMenuItem>>,
+    input: Res<Input<KeyCode>>,
+    settings: Res<Settings>,
+    mut state: ResMut<State<GameState>>,
+    mut mode: ResMut<GameMode>,
+) {
+    let mut item = confirmed(&mut events);
+    if input.just_pressed(KeyCode::P) {
+        item = Some(PauseMenuItem::Resume);
+    }
+
+    match item {
+        Some(PauseMenuItem::Resume) => resume_game(&mut state, &settings),
+        Some(PauseMenuItem::Restart) => {
+            *mode = mode.retried();
+            state.replace(GameState::MainGameLoop).unwrap();
+        }
+        Some(PauseMenuItem::Settings) => state.push(GameState::SettingsMenu).unwrap(),
+        Some(PauseMenuItem::Quit) => state.replace(GameState::StartMenu).unwrap(),
+        None => {}
     }
 }
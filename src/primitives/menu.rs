@@ -0,0 +1,422 @@
+use std::marker::PhantomData;
+use std::ops::Range;
+
+use bevy::prelude::*;
+
+use crate::layout::LayoutScaled;
+use crate::locale::{Localization, Message};
+use crate::primitives::Rec;
+use crate::theme::{ActiveTheme, FontSizes, ThemeColor};
+
+const ITEM_SPACING: f32 = 1.25;
+
+pub trait MenuAction: Copy + Send + Sync + 'static {}
+
+impl<T: Copy + Send + Sync + 'static> MenuAction for T {}
+
+pub struct MenuPlugin<T>(PhantomData<T>);
+
+impl<T> Default for MenuPlugin<T> {
+    fn default() -> Self {
+        MenuPlugin(PhantomData)
+    }
+}
+
+impl<T: MenuAction> Plugin for MenuPlugin<T> {
+    fn build(&self, app: &mut App) {
+        app.add_event::<MenuEvent<T>>()
+            .add_system(spawn_menus::<T>)
+            .add_system(navigate_menus::<T>.label(MenuSystem::Navigate))
+            .add_system(render_menus::<T>.after(MenuSystem::Navigate));
+    }
+}
+
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, SystemLabel)]
+pub enum MenuSystem {
+    Navigate,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MenuEvent<T> {
+    Confirm(T),
+    Adjust(T, i32),
+}
+
+pub fn confirmed<T: MenuAction>(events: &mut EventReader<MenuEvent<T>>) -> Option<T> {
+    events
+        .iter()
+        .filter_map(|event| match event {
+            MenuEvent::Confirm(action) => Some(*action),
+            MenuEvent::Adjust(..) => None,
+        })
+        .next_back()
+}
+
+/// Sent by every menu so feedback like sounds doesn't depend on the action type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MenuCue {
+    Move,
+    Select,
+}
+
+#[derive(Debug, Clone)]
+pub struct MenuEntry<T> {
+    pub label: Message,
+    pub action: T,
+}
+
+#[derive(Debug, Clone)]
+pub struct MenuLine {
+    pub text: Message,
+    pub highlight: bool,
+}
+
+#[derive(Debug, Clone, Component)]
+pub struct Menu<T> {
+    pub title: Message,
+    pub items: Vec<MenuEntry<T>>,
+    pub lines: Vec<MenuLine>,
+    pub back: Option<T>,
+    pub overlay: bool,
+    pub rows: Option<usize>,
+    pub focus: usize,
+}
+
+impl<T> Menu<T> {
+    pub fn new(title: impl Into<Message>) -> Self {
+        Menu {
+            title: title.into(),
+            items: Vec::new(),
+            lines: Vec::new(),
+            back: None,
+            overlay: false,
+            rows: None,
+            focus: 0,
+        }
+    }
+
+    pub fn item(mut self, label: impl Into<Message>, action: T) -> Self {
+        self.items.push(MenuEntry {
+            label: label.into(),
+            action,
+        });
+        self
+    }
+
+    pub fn line(mut self, text: impl Into<Message>) -> Self {
+        self.lines.push(MenuLine {
+            text: text.into(),
+            highlight: false,
+        });
+        self
+    }
+
+    pub fn highlight(mut self, text: impl Into<Message>) -> Self {
+        self.lines.push(MenuLine {
+            text: text.into(),
+            highlight: true,
+        });
+        self
+    }
+
+    pub fn on_back(mut self, action: T) -> Self {
+        self.back = Some(action);
+        self
+    }
+
+    pub fn with_overlay(mut self) -> Self {
+        self.overlay = true;
+        self
+    }
+
+    /// Shows at most `rows` items at a time, scrolling to keep the focus in view.
+    pub fn with_rows(mut self, rows: usize) -> Self {
+        self.rows = Some(rows);
+        self
+    }
+
+    fn visible(&self) -> Range<usize> {
+        let count = self.items.len();
+        let rows = self.rows.unwrap_or(count).min(count);
+        let first = self.focus.saturating_sub(rows / 2).min(count - rows);
+        first..first + rows
+    }
+}
+
+struct MenuMetrics {
+    spacing: f32,
+    top: f32,
+    bottom: f32,
+}
+
+impl MenuMetrics {
+    fn new(sizes: &FontSizes, rows: usize) -> Self {
+        let spacing = sizes.item * ITEM_SPACING;
+        let top = (rows as f32 * spacing) / 2.0 + sizes.title;
+
+        MenuMetrics {
+            spacing,
+            top,
+            bottom: top - sizes.title - rows as f32 * spacing,
+        }
+    }
+
+    fn item_y(&self, sizes: &FontSizes, slot: usize) -> f32 {
+        self.top - sizes.title - slot as f32 * self.spacing
+    }
+}
+
+#[derive(Component)]
+struct MenuItemText(usize);
+
+fn item_color(theme: &ActiveTheme, index: usize, focus: usize) -> Color {
+    if index == focus {
+        theme.palette.focus
+    } else {
+        theme.palette.text
+    }
+}
+
+fn spawn_menus<T: MenuAction>(
+    mut commands: Commands,
+    theme: Res<ActiveTheme>,
+    localization: Res<Localization>,
+    menus: Query<(Entity, &Menu<T>, Option<&Children>)>,
+    added: Query<(), Added<Menu<T>>>,
+) {
+    for (entity, menu, children) in menus.iter() {
+        if added.get(entity).is_ok() {
+            commands
+                .entity(entity)
+                .insert_bundle((Transform::default(), GlobalTransform::default()))
+                .insert(LayoutScaled);
+        } else if theme.is_changed() || localization.is_changed() {
+            for child in children.iter().flat_map(|children| children.iter()) {
+                commands.entity(*child).despawn_recursive();
+            }
+        } else {
+            continue;
+        }
+
+        let sizes = theme.sizes;
+        let palette = theme.palette;
+        let text_style = |font_size, color| TextStyle {
+            font: theme.font.clone(),
+            font_size,
+            color,
+        };
+        let centered = TextAlignment {
+            vertical: VerticalAlign::Center,
+            horizontal: HorizontalAlign::Center,
+        };
+        let at = |y| Transform {
+            translation: Vec3::new(0.0, y, 2.1),
+            ..Default::default()
+        };
+
+        let visible = menu.visible();
+        let metrics = MenuMetrics::new(&sizes, visible.len());
+
+        commands.entity(entity).with_children(|parent| {
+            parent.spawn_bundle(Text2dBundle {
+                text: Text::with_section(
+                    localization.resolve(&menu.title),
+                    text_style(sizes.title, palette.text),
+                    centered,
+                ),
+                transform: at(metrics.top),
+                ..Default::default()
+            });
+
+            for (index, item) in menu.items.iter().enumerate() {
+                let slot = index.saturating_sub(visible.start);
+                parent
+                    .spawn_bundle(Text2dBundle {
+                        text: Text::with_section(
+                            localization.resolve(&item.label),
+                            text_style(sizes.item, item_color(&theme, index, menu.focus)),
+                            centered,
+                        ),
+                        transform: at(metrics.item_y(&sizes, slot)),
+                        visibility: Visibility {
+                            is_visible: visible.contains(&index),
+                        },
+                        ..Default::default()
+                    })
+                    .insert(MenuItemText(index));
+            }
+
+            if !menu.lines.is_empty() {
+                let sections = menu
+                    .lines
+                    .iter()
+                    .enumerate()
+                    .map(|(index, line)| TextSection {
+                        value: if index == 0 {
+                            localization.resolve(&line.text)
+                        } else {
+                            format!("\n{}", localization.resolve(&line.text))
+                        },
+                        style: text_style(
+                            sizes.body,
+                            if line.highlight {
+                                palette.focus
+                            } else {
+                                palette.text
+                            },
+                        ),
+                    })
+                    .collect();
+
+                parent.spawn_bundle(Text2dBundle {
+                    text: Text {
+                        sections,
+                        alignment: TextAlignment {
+                            vertical: VerticalAlign::Top,
+                            horizontal: HorizontalAlign::Center,
+                        },
+                    },
+                    transform: at(metrics.bottom),
+                    ..Default::default()
+                });
+            }
+
+            if menu.overlay {
+                parent
+                    .spawn_bundle(SpriteBundle {
+                        sprite: Sprite {
+                            color: palette.overlay,
+                            ..Default::default()
+                        },
+                        transform: Transform {
+                            translation: Vec3::new(0.0, 0.0, 2.0),
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    })
+                    .insert(ThemeColor::Overlay)
+                    .insert(Rec {
+                        width: 10000,
+                        height: 10000,
+                    });
+            }
+        });
+    }
+}
+
+fn just_pressed(
+    keys: &mut Input<KeyCode>,
+    buttons: &mut Input<GamepadButton>,
+    gamepads: &Gamepads,
+    key_codes: &[KeyCode],
+    button_type: GamepadButtonType,
+) -> bool {
+    let mut pressed = false;
+
+    for key in key_codes {
+        if keys.just_pressed(*key) {
+            keys.reset(*key);
+            pressed = true;
+        }
+    }
+
+    for gamepad in gamepads.iter() {
+        let button = GamepadButton(*gamepad, button_type);
+        if buttons.just_pressed(button) {
+            buttons.reset(button);
+            pressed = true;
+        }
+    }
+
+    pressed
+}
+
+fn navigate_menus<T: MenuAction>(
+    mut keys: ResMut<Input<KeyCode>>,
+    mut buttons: ResMut<Input<GamepadButton>>,
+    gamepads: Res<Gamepads>,
+    mut events: EventWriter<MenuEvent<T>>,
+    mut cues: EventWriter<MenuCue>,
+    mut menus: Query<&mut Menu<T>>,
+) {
+    for mut menu in menus.iter_mut() {
+        let count = menu.items.len();
+        if count == 0 {
+            continue;
+        }
+
+        let mut pressed = |key_codes: &[KeyCode], button_type| {
+            just_pressed(&mut keys, &mut buttons, &gamepads, key_codes, button_type)
+        };
+
+        let mut focus = menu.focus.min(count - 1);
+        if pressed(&[KeyCode::Up, KeyCode::W], GamepadButtonType::DPadUp) {
+            focus = (focus + count - 1) % count;
+        }
+        if pressed(&[KeyCode::Down, KeyCode::S], GamepadButtonType::DPadDown) {
+            focus = (focus + 1) % count;
+        }
+        if menu.focus != focus {
+            menu.focus = focus;
+            cues.send(MenuCue::Move);
+        }
+
+        let focused = menu.items[focus].action;
+        if pressed(&[KeyCode::Left, KeyCode::A], GamepadButtonType::DPadLeft) {
+            events.send(MenuEvent::Adjust(focused, -1));
+            cues.send(MenuCue::Select);
+        }
+        if pressed(&[KeyCode::Right, KeyCode::D], GamepadButtonType::DPadRight) {
+            events.send(MenuEvent::Adjust(focused, 1));
+            cues.send(MenuCue::Select);
+        }
+        if pressed(&[KeyCode::Return, KeyCode::Space], GamepadButtonType::South) {
+            events.send(MenuEvent::Confirm(focused));
+            cues.send(MenuCue::Select);
+        } else if let Some(back) = menu.back {
+            if pressed(&[KeyCode::Back], GamepadButtonType::East) {
+                events.send(MenuEvent::Confirm(back));
+                cues.send(MenuCue::Select);
+            }
+        }
+    }
+}
+
+fn render_menus<T: MenuAction>(
+    theme: Res<ActiveTheme>,
+    localization: Res<Localization>,
+    menus: Query<(&Menu<T>, &Children), Changed<Menu<T>>>,
+    mut items: Query<(&MenuItemText, &mut Text, &mut Transform, &mut Visibility)>,
+) {
+    for (menu, children) in menus.iter() {
+        let visible = menu.visible();
+        let metrics = MenuMetrics::new(&theme.sizes, visible.len());
+
+        for child in children.iter() {
+            let (item, mut text, mut transform, mut visibility) = match items.get_mut(*child) {
+                Ok(item) => item,
+                Err(_) => continue,
+            };
+            let entry = match menu.items.get(item.0) {
+                Some(entry) => entry,
+                None => continue,
+            };
+
+            let label = localization.resolve(&entry.label);
+            let section = &mut text.sections[0];
+            if section.value != label {
+                section.value = label;
+            }
+            section.style.color = item_color(&theme, item.0, menu.focus);
+
+            let shown = visible.contains(&item.0);
+            if visibility.is_visible != shown {
+                visibility.is_visible = shown;
+            }
+            if shown {
+                let slot = item.0 - visible.start;
+                transform.translation.y = metrics.item_y(&theme.sizes, slot);
+            }
+        }
+    }
+}
@@ -1,27 +1,54 @@
+pub mod menu;
+
+use std::f32::consts::{FRAC_PI_2, PI};
 use std::ops::{Add, AddAssign, Neg, Sub};
 
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
-use crate::arena::{ARENA_BUFFER, ARENA_HEIGHT, ARENA_WIDTH};
+use crate::arena::{ArenaSize, ARENA_BUFFER};
+use crate::hud::HUD_HEIGHT;
+use crate::layout::Layout;
+use crate::settings::{CameraMode, Settings};
 
 pub struct PrimitivesPlugin;
 
 impl Plugin for PrimitivesPlugin {
     fn build(&self, app: &mut App) {
-        app.add_system(update_transform_with_changed_changed_scale)
-            .add_system(update_transform_with_changed_position)
+        app.add_system(update_transform_with_changed_changed_scale.label(PositionSystem::Snap))
+            .add_system(update_transform_with_changed_position.label(PositionSystem::Snap))
+            .add_system(
+                interpolate_transforms
+                    .label(PositionSystem::Interpolate)
+                    .after(PositionSystem::Snap),
+            )
             .add_system(scale_positions)
             .add_system(scale_changed_positions)
             .add_system(update_scaling)
-            .add_system(render_progress_bars)
+            .add_event::<menu::MenuCue>()
             .init_resource::<Scaling>();
     }
 }
 
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, SystemLabel)]
+pub enum PositionSystem {
+    Snap,
+    Interpolate,
+}
+
+/// World units per grid cell.
 #[derive(Default)]
-struct Scaling(f32);
+pub struct Scaling(pub f32);
+
+/// Lerps an entity's Transform from `previous` to its current Position as
+/// `progress` goes from 0 to 1. Jumps of more than one cell are snapped.
+#[derive(Debug, Default, Component, Clone, Copy)]
+pub struct Interpolation {
+    pub previous: Position,
+    pub progress: f32,
+}
 
-#[derive(Debug, Default, Component, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Default, Component, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Position {
     pub x: i32,
     pub y: i32,
@@ -64,51 +91,6 @@ impl AddAssign<Position> for Position {
     }
 }
 
-#[derive(Component, Debug, Clone, Copy)]
-pub struct Bar {
-    pub bottom_left: Vec2,
-    pub top_right: Vec2,
-}
-
-fn render_bar(bar: Bar, transform: &mut Transform) {
-    let scale_x = bar.top_right.x - bar.bottom_left.x;
-    let x = bar.bottom_left.x + (scale_x / 2.0);
-
-    let scale_y = bar.top_right.y - bar.bottom_left.y;
-    let y = bar.bottom_left.y + (scale_y / 2.0);
-
-    transform.translation.x = x;
-    transform.translation.y = y;
-
-    transform.scale.x = scale_x;
-    transform.scale.y = scale_y;
-}
-
-#[derive(Component, Debug, Clone, Copy)]
-pub struct ProgressBar {
-    pub bar: Bar,
-    pub percent: f32,
-}
-
-fn render_progress_bar(progress_bar: ProgressBar, transform: &mut Transform) {
-    let bar = progress_bar.bar;
-    let scale_x = bar.top_right.x - bar.bottom_left.x;
-    let x = bar.bottom_left.x + (scale_x * progress_bar.percent);
-
-    let bar = Bar {
-        bottom_left: bar.bottom_left,
-        top_right: Vec2::new(x, bar.top_right.y),
-    };
-
-    render_bar(bar, transform);
-}
-
-fn render_progress_bars(mut bars: Query<(&ProgressBar, &mut Transform), Changed<ProgressBar>>) {
-    for (bar, mut transform) in bars.iter_mut() {
-        render_progress_bar(*bar, &mut transform);
-    }
-}
-
 #[derive(Component)]
 pub struct Rec {
     pub width: i32,
@@ -126,12 +108,20 @@ impl Rec {
     }
 }
 
-fn update_scaling(mut scaling: ResMut<Scaling>, windows: Res<Windows>) {
-    let window = windows.get_primary().unwrap();
-    let scale = if window.height().partial_cmp(&window.width()) == Some(std::cmp::Ordering::Less) {
-        window.height() / (ARENA_HEIGHT + ARENA_BUFFER)
-    } else {
-        window.width() / (ARENA_WIDTH + ARENA_BUFFER)
+fn update_scaling(
+    mut scaling: ResMut<Scaling>,
+    settings: Res<Settings>,
+    layout: Res<Layout>,
+    arena: Res<ArenaSize>,
+) {
+    let scale = match settings.video.camera {
+        CameraMode::Fit => f32::min(
+            (layout.viewport.y - HUD_HEIGHT * layout.scale * 2.0)
+                / (arena.height as f32 + ARENA_BUFFER),
+            layout.viewport.x / (arena.width as f32 + ARENA_BUFFER),
+        )
+        .max(0.0),
+        CameraMode::Follow => settings.video.cell_size * layout.scale,
     };
 
     if scaling.0 != scale {
@@ -198,11 +188,46 @@ fn update_transforms_generic<'a>(
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+fn interpolate_transforms(
+    settings: Res<Settings>,
+    scaling: Res<Scaling>,
+    mut q: Query<(&Position, &Interpolation, &mut Transform)>,
+) {
+    let scale = scaling.0;
+
+    if !settings.video.smooth_movement {
+        if settings.is_changed() {
+            update_transforms_generic(
+                scale,
+                q.iter_mut()
+                    .map(|(position, _, transform)| (position, transform)),
+            );
+        }
+        return;
+    }
+
+    for (position, interpolation, mut transform) in q.iter_mut() {
+        let delta = *position - interpolation.previous;
+        let from = if delta.x.abs() > 1 || delta.y.abs() > 1 {
+            *position
+        } else {
+            interpolation.previous
+        };
+
+        let t = interpolation.progress.clamp(0.0, 1.0);
+        let x = from.x as f32 + (position.x - from.x) as f32 * t;
+        let y = from.y as f32 + (position.y - from.y) as f32 * t;
+        transform.translation.x = x * scale;
+        transform.translation.y = y * scale;
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Direction {
     Up,
     Down,
     Left,
+    #[default]
     Right,
 }
 
@@ -215,11 +240,36 @@ impl Direction {
             Self::Down => Self::Up,
         }
     }
-}
 
-impl Default for Direction {
-    fn default() -> Self {
-        Direction::Right
+    pub fn counter_clockwise(self) -> Self {
+        match self {
+            Self::Right => Self::Up,
+            Self::Up => Self::Left,
+            Self::Left => Self::Down,
+            Self::Down => Self::Right,
+        }
+    }
+
+    /// Rotation around z that turns +x into this direction.
+    pub fn angle(self) -> f32 {
+        match self {
+            Self::Right => 0.0,
+            Self::Up => FRAC_PI_2,
+            Self::Left => PI,
+            Self::Down => -FRAC_PI_2,
+        }
+    }
+
+    /// The direction of the step from `from` to the neighbouring cell `to`, if
+    /// they are neighbours.
+    pub fn between(from: Position, to: Position) -> Option<Self> {
+        match to - from {
+            Position { x: 0, y: 1 } => Some(Self::Up),
+            Position { x: 0, y: -1 } => Some(Self::Down),
+            Position { x: -1, y: 0 } => Some(Self::Left),
+            Position { x: 1, y: 0 } => Some(Self::Right),
+            _ => None,
+        }
     }
 }
 
@@ -0,0 +1,197 @@
+use std::collections::VecDeque;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use bevy::prelude::*;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+
+use crate::arena::ArenaSize;
+use crate::game_loop::{FoodRules, GameStage, Score, TickEvent};
+use crate::game_state::{GameMode, GameState};
+use crate::primitives::{Direction, Position};
+use crate::settings::Settings;
+
+const REPLAY_DIR: &str = "replays";
+
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Seed>()
+            .init_resource::<GameRng>()
+            .init_resource::<Recording>()
+            .add_system_set(
+                SystemSet::on_enter(GameState::MainGameLoop)
+                    .with_system(start_recording.after(GameStage::Setup)),
+            )
+            .add_system_set(
+                SystemSet::on_update(GameState::MainGameLoop).with_system(record_frames),
+            )
+            .add_system_set(SystemSet::on_enter(GameState::GameOver).with_system(save_replay));
+    }
+}
+
+pub struct Seed(pub u64);
+
+impl Default for Seed {
+    fn default() -> Self {
+        Seed(rand::random())
+    }
+}
+
+pub struct GameRng(pub StdRng);
+
+impl Default for GameRng {
+    fn default() -> Self {
+        GameRng(StdRng::from_entropy())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Frame {
+    pub heading: Direction,
+    pub head: Position,
+    pub tail: usize,
+    #[serde(default)]
+    pub food: Vec<Position>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Replay {
+    pub seed: u64,
+    pub arena_width: i32,
+    pub arena_height: i32,
+    #[serde(default)]
+    pub food_count: usize,
+    #[serde(default)]
+    pub food_growth: usize,
+    /// Whether the seed came from a daily challenge rather than chance.
+    #[serde(default)]
+    pub daily: bool,
+    pub score: usize,
+    pub frames: Vec<Frame>,
+}
+
+impl Replay {
+    pub fn new(seed: u64, arena: ArenaSize, rules: FoodRules, mode: GameMode) -> Self {
+        Replay {
+            seed,
+            arena_width: arena.width,
+            arena_height: arena.height,
+            food_count: rules.count,
+            food_growth: rules.growth,
+            daily: mode != GameMode::Classic,
+            ..Default::default()
+        }
+    }
+
+    pub fn best(&self) -> Option<Replay> {
+        Replay::load(self.best_path()).ok()
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), String> {
+        let contents = ron::to_string(self).map_err(|e| e.to_string())?;
+        if let Some(directory) = path.as_ref().parent() {
+            fs::create_dir_all(directory).map_err(|e| e.to_string())?;
+        }
+        fs::write(path, contents).map_err(|e| e.to_string())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Replay, String> {
+        let contents = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        ron::from_str(&contents).map_err(|e| e.to_string())
+    }
+
+    /// Dailies keep a best run per seed. Classic keeps one per arena and food
+    /// rules, and its ghost runs start from that best run's seed.
+    fn best_path(&self) -> PathBuf {
+        let name = if self.daily {
+            format!(
+                "best-{}-{}x{}.ron",
+                self.seed, self.arena_width, self.arena_height
+            )
+        } else {
+            format!(
+                "best-classic-{}x{}-food-{}-{}.ron",
+                self.arena_width, self.arena_height, self.food_count, self.food_growth
+            )
+        };
+        PathBuf::from(REPLAY_DIR).join(name)
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct Playback {
+    pub head: Position,
+    pub tail: VecDeque<Position>,
+    frame: usize,
+}
+
+impl Playback {
+    pub fn step<'a>(&mut self, replay: &'a Replay) -> Option<&'a Frame> {
+        let frame = replay.frames.get(self.frame)?;
+        self.frame += 1;
+
+        self.tail.push_front(self.head);
+        self.tail.truncate(frame.tail);
+        self.head = frame.head;
+
+        Some(frame)
+    }
+}
+
+#[derive(Default)]
+pub struct Recording(pub Replay);
+
+fn start_recording(
+    settings: Res<Settings>,
+    arena: Res<ArenaSize>,
+    rules: Res<FoodRules>,
+    mode: Res<GameMode>,
+    mut seed: ResMut<Seed>,
+    mut rng: ResMut<GameRng>,
+    mut recording: ResMut<Recording>,
+) {
+    let mut replay = Replay::new(seed.0, *arena, *rules, *mode);
+    if settings.gameplay.ghost && *mode == GameMode::Classic {
+        if let Some(best) = replay.best() {
+            seed.0 = best.seed;
+            replay.seed = best.seed;
+        }
+    }
+
+    rng.0 = StdRng::seed_from_u64(seed.0);
+    recording.0 = replay;
+}
+
+fn record_frames(mut events: EventReader<TickEvent>, mut recording: ResMut<Recording>) {
+    for event in events.iter() {
+        recording.0.frames.push(Frame {
+            heading: event.heading,
+            head: event.position,
+            tail: event.tail,
+            food: event.food.clone(),
+        });
+    }
+}
+
+fn save_replay(score: Res<Score>, mut recording: ResMut<Recording>) {
+    recording.0.score = score.0;
+
+    if let Err(error) = recording.0.save(PathBuf::from(REPLAY_DIR).join("last.ron")) {
+        error!("failed to save replay: {}", error);
+    }
+
+    let is_best = recording
+        .0
+        .best()
+        .is_none_or(|best| recording.0.score > best.score);
+
+    if is_best {
+        if let Err(error) = recording.0.save(recording.0.best_path()) {
+            error!("failed to save replay: {}", error);
+        }
+    }
+}
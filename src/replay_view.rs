@@ -0,0 +1,163 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::arena::ArenaSize;
+use crate::game_state::{CleanUp, GameState};
+use crate::locale::{LocalizedText, Message};
+use crate::primitives::{Position, Rec};
+use crate::replay::{Playback, Recording, Replay};
+use crate::settings::Settings;
+use crate::theme::{ActiveTheme, ThemeColor};
+
+pub struct ReplayViewPlugin;
+
+impl Plugin for ReplayViewPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(SystemSet::on_enter(GameState::ReplayView).with_system(spawn_viewer))
+            .add_system_set(
+                SystemSet::on_update(GameState::ReplayView)
+                    .with_system(play_replay)
+                    .with_system(close_viewer),
+            );
+    }
+}
+
+#[derive(Component)]
+struct ReplayViewer {
+    replay: Replay,
+    playback: Playback,
+    timer: Timer,
+}
+
+#[derive(Component)]
+struct ReplaySegment;
+
+fn spawn_viewer(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    theme: Res<ActiveTheme>,
+    arena: Res<ArenaSize>,
+    recording: Res<Recording>,
+) {
+    commands
+        .spawn_bundle(SpriteBundle {
+            sprite: Sprite {
+                color: theme.palette.arena,
+                ..Default::default()
+            },
+            transform: Transform {
+                translation: Vec3::new(0.0, 0.0, 1.7),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .insert(ThemeColor::Arena)
+        .insert(Position { x: 0, y: 0 })
+        .insert(Rec {
+            width: arena.width,
+            height: arena.height,
+        })
+        .insert(ReplayViewer {
+            replay: recording.0.clone(),
+            playback: Playback::default(),
+            timer: Timer::new(Duration::from_millis(settings.gameplay.tick_millis), true),
+        })
+        .insert(CleanUp::new(GameState::ReplayView));
+
+    commands
+        .spawn_bundle(TextBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: Rect {
+                    bottom: Val::Px(30.0),
+                    left: Val::Px(10.0),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            text: Text::with_section(
+                String::new(),
+                TextStyle {
+                    font: theme.font.clone(),
+                    font_size: theme.sizes.body,
+                    color: theme.palette.text,
+                },
+                Default::default(),
+            ),
+            ..Default::default()
+        })
+        .insert(ThemeColor::Text)
+        .insert(LocalizedText(Message::new("replay.hint")))
+        .insert(CleanUp::new(GameState::ReplayView));
+}
+
+fn play_replay(
+    mut commands: Commands,
+    time: Res<Time>,
+    theme: Res<ActiveTheme>,
+    mut viewers: Query<&mut ReplayViewer>,
+    segments: Query<Entity, With<ReplaySegment>>,
+) {
+    for mut viewer in viewers.iter_mut() {
+        if !viewer.timer.tick(time.delta()).just_finished() {
+            continue;
+        }
+
+        let ReplayViewer {
+            replay, playback, ..
+        } = viewer.as_mut();
+        let food = match playback.step(replay) {
+            Some(frame) => frame.food.clone(),
+            None => {
+                *playback = Playback::default();
+                continue;
+            }
+        };
+
+        for entity in segments.iter() {
+            commands.entity(entity).despawn();
+        }
+
+        let cells = playback
+            .tail
+            .iter()
+            .chain(Some(&playback.head))
+            .map(|position| (*position, ThemeColor::Snake))
+            .chain(
+                food.into_iter()
+                    .map(|position| (position, ThemeColor::Food)),
+            );
+
+        for (position, role) in cells {
+            commands
+                .spawn_bundle(SpriteBundle {
+                    sprite: Sprite {
+                        color: theme.palette.color(role),
+                        ..Default::default()
+                    },
+                    transform: Transform {
+                        translation: Vec3::new(0.0, 0.0, 1.8),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                })
+                .insert(ReplaySegment)
+                .insert(role)
+                .insert(position)
+                .insert(Rec {
+                    width: 1,
+                    height: 1,
+                })
+                .insert(CleanUp::new(GameState::ReplayView));
+        }
+    }
+}
+
+fn close_viewer(mut input: ResMut<Input<KeyCode>>, mut state: ResMut<State<GameState>>) {
+    if input.just_pressed(KeyCode::Return) || input.just_pressed(KeyCode::Back) {
+        input.reset(KeyCode::Return);
+        input.reset(KeyCode::Back);
+        state.pop().unwrap();
+    }
+}
@@ -0,0 +1,261 @@
+use std::collections::VecDeque;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::arena::ArenaSize;
+use crate::game_loop::FoodRules;
+use crate::primitives::Rec;
+
+pub use crate::primitives::{Direction, Position};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeathCause {
+    Wall,
+    Tail,
+}
+
+pub fn can_turn(direction: Direction, wanted: Direction) -> bool {
+    wanted != direction.opposite()
+}
+
+pub fn advance(position: Position, heading: Direction) -> Position {
+    position + heading.into()
+}
+
+pub fn hits_wall(arena: ArenaSize, position: Position) -> bool {
+    let rec = Rec {
+        width: arena.width,
+        height: arena.height,
+    };
+    !rec.contains(position)
+}
+
+pub fn spawn_food(rng: &mut impl Rng, arena: ArenaSize) -> Position {
+    let width = arena.width as f32 / 2.0;
+    let height = arena.height as f32 / 2.0;
+    Position {
+        x: rng.gen_range(-width..width) as i32,
+        y: rng.gen_range(-height..height) as i32,
+    }
+}
+
+/// The snake's body and where it is heading. Both frontends move it with
+/// `Snake::step`, so movement, eating, growth and collisions can't drift apart.
+#[derive(Debug, Clone, Default)]
+pub struct Snake {
+    pub head: Position,
+    /// Segments from the tail tip at the front to the neck at the back.
+    pub tail: VecDeque<Position>,
+    pub direction: Direction,
+    /// How many segments the tail grows to.
+    pub length: usize,
+    pub cause: Option<DeathCause>,
+    heading: Direction,
+}
+
+/// What a single `Snake::step` did.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    /// Indices into the food passed to `Snake::step` that the head landed on.
+    pub eaten: Vec<usize>,
+}
+
+impl Snake {
+    pub fn is_dead(&self) -> bool {
+        self.cause.is_some()
+    }
+
+    pub fn steer(&mut self, wanted: Direction) {
+        if can_turn(self.direction, wanted) {
+            self.heading = wanted;
+        }
+    }
+
+    /// Moves the snake one cell, eating any of `food` it lands on, and
+    /// returns `None` once it is dead.
+    pub fn step(&mut self, arena: ArenaSize, rules: FoodRules, food: &[Position]) -> Option<Step> {
+        if self.is_dead() {
+            return None;
+        }
+
+        let from = self.head;
+        self.head = advance(self.head, self.heading);
+        self.direction = self.heading;
+
+        self.tail.push_back(from);
+        if self.tail.len() > self.length {
+            self.tail.pop_front();
+        }
+
+        let eaten: Vec<usize> = food
+            .iter()
+            .enumerate()
+            .filter(|(_, food)| **food == self.head)
+            .map(|(index, _)| index)
+            .collect();
+        self.length += eaten.len() * rules.growth;
+
+        if hits_wall(arena, self.head) {
+            self.cause = Some(DeathCause::Wall);
+        } else if self.tail.contains(&self.head) {
+            self.cause = Some(DeathCause::Tail);
+        }
+
+        Some(Step { eaten })
+    }
+}
+
+/// A whole run without any rendering, stepped one tick at a time. Frontends
+/// that don't use Bevy drive this directly; it moves the snake with the same
+/// `Snake::step` as the Bevy systems and draws food from the seeded RNG in the
+/// same order.
+pub struct Game {
+    pub arena: ArenaSize,
+    pub rules: FoodRules,
+    pub snake: Snake,
+    pub food: Vec<Position>,
+    pub score: usize,
+    rng: StdRng,
+}
+
+impl Game {
+    pub fn new(seed: u64, arena: ArenaSize, rules: FoodRules) -> Self {
+        let mut game = Game {
+            arena,
+            rules,
+            snake: Snake::default(),
+            food: Vec::new(),
+            score: 0,
+            rng: StdRng::seed_from_u64(seed),
+        };
+        game.refill_food();
+        game
+    }
+
+    pub fn length(&self) -> usize {
+        self.snake.tail.len() + 1
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.snake.is_dead()
+    }
+
+    pub fn steer(&mut self, wanted: Direction) {
+        self.snake.steer(wanted);
+    }
+
+    /// Moves the snake one cell and returns how much food it ate.
+    pub fn tick(&mut self) -> usize {
+        let step = match self.snake.step(self.arena, self.rules, &self.food) {
+            Some(step) => step,
+            None => return 0,
+        };
+
+        for index in step.eaten.iter().rev() {
+            self.food.remove(*index);
+        }
+        self.score += step.eaten.len();
+
+        self.refill_food();
+        step.eaten.len()
+    }
+
+    fn refill_food(&mut self) {
+        while self.food.len() < self.rules.count {
+            let food = spawn_food(&mut self.rng, self.arena);
+            self.food.push(food);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ARENA: ArenaSize = ArenaSize {
+        width: 11,
+        height: 11,
+    };
+
+    fn toward(snake: &Snake, target: Position) -> Direction {
+        let wanted = if target.x > snake.head.x {
+            Direction::Right
+        } else if target.x < snake.head.x {
+            Direction::Left
+        } else if target.y > snake.head.y {
+            Direction::Up
+        } else {
+            Direction::Down
+        };
+
+        if can_turn(snake.direction, wanted) {
+            wanted
+        } else if snake.head.y < target.y {
+            Direction::Up
+        } else {
+            Direction::Down
+        }
+    }
+
+    #[test]
+    fn seeded_game_eats_grows_and_hits_the_wall() {
+        let rules = FoodRules {
+            count: 2,
+            growth: 2,
+        };
+        let mut game = Game::new(7, ARENA, rules);
+        let mut again = Game::new(7, ARENA, rules);
+        assert_eq!(game.food, again.food);
+
+        let mut ticks = 0;
+        while game.score < 3 {
+            let direction = toward(&game.snake, game.food[0]);
+            game.steer(direction);
+            again.steer(direction);
+
+            let eaten = game.tick();
+            assert_eq!(eaten, again.tick());
+            assert_eq!(game.snake.head, again.snake.head);
+            assert_eq!(game.food, again.food);
+            assert_eq!(game.food.len(), rules.count);
+            assert!(!game.is_over(), "died after {} ticks", ticks);
+
+            ticks += 1;
+            assert!(ticks < 500, "never reached the food");
+        }
+        assert_eq!(game.snake.length, 3 * rules.growth);
+
+        while !game.is_over() {
+            game.tick();
+        }
+        assert!(hits_wall(ARENA, game.snake.head));
+        assert_eq!(game.snake.cause, Some(DeathCause::Wall));
+
+        let head = game.snake.head;
+        assert_eq!(game.tick(), 0);
+        assert_eq!(game.snake.head, head);
+    }
+
+    #[test]
+    fn snake_dies_biting_its_tail() {
+        let mut snake = Snake {
+            length: 4,
+            ..Default::default()
+        };
+        let rules = FoodRules::default();
+
+        for direction in [Direction::Right, Direction::Right, Direction::Up] {
+            snake.steer(direction);
+            snake.step(ARENA, rules, &[]);
+        }
+        for direction in [Direction::Left, Direction::Down] {
+            snake.steer(direction);
+            assert!(!snake.is_dead());
+            snake.step(ARENA, rules, &[]);
+        }
+
+        assert_eq!(snake.cause, Some(DeathCause::Tail));
+        assert_eq!(snake.step(ARENA, rules, &[]), None);
+    }
+}
@@ -0,0 +1,345 @@
+use std::fmt;
+use std::fs;
+use std::io;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::arena::{ArenaSize, ARENA_HEIGHT, ARENA_WIDTH};
+use crate::game_loop::FoodRules;
+
+const SETTINGS_PATH: &str = "settings.ron";
+
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        let (settings, error) = match Settings::load() {
+            Ok(settings) => (settings, None),
+            Err(error) => (Settings::default(), Some(error)),
+        };
+
+        app.insert_resource(settings.video.window_descriptor())
+            .insert_resource(settings)
+            .insert_resource(SettingsLoadError(error))
+            .add_startup_system(report_load_error)
+            .add_system(save_settings);
+    }
+}
+
+#[derive(Debug)]
+pub enum SettingsError {
+    Io(io::Error),
+    Parse(ron::Error),
+    Invalid(String),
+}
+
+impl fmt::Display for SettingsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SettingsError::Io(error) => write!(f, "could not access {}: {}", SETTINGS_PATH, error),
+            SettingsError::Parse(error) => {
+                write!(f, "could not parse {}: {}", SETTINGS_PATH, error)
+            }
+            SettingsError::Invalid(message) => write!(f, "invalid {}: {}", SETTINGS_PATH, message),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub language: String,
+    pub gameplay: GameplaySettings,
+    pub video: VideoSettings,
+    pub audio: AudioSettings,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            language: "en".to_string(),
+            gameplay: GameplaySettings::default(),
+            video: VideoSettings::default(),
+            audio: AudioSettings::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GameplaySettings {
+    pub tick_millis: u64,
+    pub arena_width: i32,
+    pub arena_height: i32,
+    pub food_count: usize,
+    pub food_growth: usize,
+    pub ghost: bool,
+    pub countdown_seconds: u32,
+}
+
+impl Default for GameplaySettings {
+    fn default() -> Self {
+        GameplaySettings {
+            tick_millis: 100,
+            arena_width: ARENA_WIDTH as i32,
+            arena_height: ARENA_HEIGHT as i32,
+            food_count: 1,
+            food_growth: 1,
+            ghost: true,
+            countdown_seconds: 3,
+        }
+    }
+}
+
+impl GameplaySettings {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(10..=2000).contains(&self.tick_millis) {
+            return Err("gameplay.tick_millis must be between 10 and 2000".to_string());
+        }
+        if !(5..=101).contains(&self.arena_width) || !(5..=101).contains(&self.arena_height) {
+            return Err(
+                "gameplay.arena_width and arena_height must be between 5 and 101".to_string(),
+            );
+        }
+        if self.food_count == 0 || self.food_growth == 0 {
+            return Err("gameplay.food_count and food_growth must be at least 1".to_string());
+        }
+        if self.countdown_seconds > 10 {
+            return Err("gameplay.countdown_seconds must be at most 10".to_string());
+        }
+
+        Ok(())
+    }
+
+    /// Rounds even arena sizes up to the next odd one. Cells are centred on
+    /// whole coordinates around the middle of the arena, which only lines up
+    /// with its edges when there's an odd number of them.
+    pub fn round_arena(&mut self) {
+        self.arena_width |= 1;
+        self.arena_height |= 1;
+    }
+
+    pub fn arena(&self) -> ArenaSize {
+        ArenaSize {
+            width: self.arena_width,
+            height: self.arena_height,
+        }
+    }
+
+    pub fn food_rules(&self) -> FoodRules {
+        FoodRules {
+            count: self.food_count,
+            growth: self.food_growth,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VideoSettings {
+    pub window_width: f32,
+    pub window_height: f32,
+    pub vsync: bool,
+    pub letterbox: bool,
+    pub smooth_movement: bool,
+    pub particles: bool,
+    pub particle_amount: f32,
+    pub camera: CameraMode,
+    pub arena_pattern: ArenaPattern,
+    pub palette: PaletteMode,
+    pub shape_markers: bool,
+    pub cell_size: f32,
+    pub screen_shake: bool,
+    pub theme: String,
+}
+
+impl Default for VideoSettings {
+    fn default() -> Self {
+        VideoSettings {
+            window_width: 1280.0,
+            window_height: 720.0,
+            vsync: true,
+            letterbox: false,
+            smooth_movement: true,
+            particles: true,
+            particle_amount: 1.0,
+            camera: CameraMode::Fit,
+            arena_pattern: ArenaPattern::Plain,
+            palette: PaletteMode::Theme,
+            shape_markers: false,
+            cell_size: 32.0,
+            screen_shake: true,
+            theme: "default".to_string(),
+        }
+    }
+}
+
+/// How the arena is framed: scaled to fit the window, or drawn at a fixed
+/// `cell_size` with the camera following the snake.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CameraMode {
+    Fit,
+    Follow,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ArenaPattern {
+    Plain,
+    Grid,
+    Checkerboard,
+}
+
+/// Replaces the theme's colours with a built-in palette that stays readable
+/// for colour-blind players or at high contrast.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PaletteMode {
+    Theme,
+    Deuteranopia,
+    Protanopia,
+    Tritanopia,
+    HighContrast,
+}
+
+impl VideoSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.window_width <= 0.0 || self.window_height <= 0.0 {
+            return Err("video.window_width and window_height must be positive".to_string());
+        }
+        if !(0.0..=4.0).contains(&self.particle_amount) {
+            return Err("video.particle_amount must be between 0.0 and 4.0".to_string());
+        }
+        if self.cell_size < 4.0 {
+            return Err("video.cell_size must be at least 4.0".to_string());
+        }
+        if self.theme.is_empty() {
+            return Err("video.theme must name a theme under assets/themes/".to_string());
+        }
+
+        Ok(())
+    }
+
+    fn window_descriptor(&self) -> WindowDescriptor {
+        WindowDescriptor {
+            width: self.window_width,
+            height: self.window_height,
+            vsync: self.vsync,
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AudioSettings {
+    pub master_volume: f32,
+    pub music_volume: f32,
+    pub sfx_volume: f32,
+    pub muted: bool,
+    pub audio_cues: bool,
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        AudioSettings {
+            master_volume: 1.0,
+            music_volume: 0.5,
+            sfx_volume: 0.8,
+            muted: false,
+            audio_cues: false,
+        }
+    }
+}
+
+impl AudioSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        if [self.master_volume, self.music_volume, self.sfx_volume]
+            .iter()
+            .any(|volume| !(0.0..=1.0).contains(volume))
+        {
+            return Err("audio volumes must be between 0.0 and 1.0".to_string());
+        }
+
+        Ok(())
+    }
+
+    pub fn music_gain(&self) -> f32 {
+        self.gain(self.music_volume)
+    }
+
+    pub fn sfx_gain(&self) -> f32 {
+        self.gain(self.sfx_volume)
+    }
+
+    fn gain(&self, volume: f32) -> f32 {
+        if self.muted {
+            0.0
+        } else {
+            self.master_volume * volume
+        }
+    }
+}
+
+impl Settings {
+    pub fn load() -> Result<Settings, SettingsError> {
+        let contents = match fs::read_to_string(SETTINGS_PATH) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Settings::default()),
+            Err(error) => return Err(SettingsError::Io(error)),
+        };
+
+        let mut settings: Settings = ron::from_str(&contents).map_err(SettingsError::Parse)?;
+        settings.gameplay.round_arena();
+        settings.validate()?;
+        Ok(settings)
+    }
+
+    pub fn save(&self) -> Result<(), SettingsError> {
+        let contents =
+            ron::ser::to_string_pretty(self, Default::default()).map_err(SettingsError::Parse)?;
+        fs::write(SETTINGS_PATH, contents).map_err(SettingsError::Io)
+    }
+
+    pub fn validate(&self) -> Result<(), SettingsError> {
+        if self.language.is_empty() {
+            return Err(SettingsError::Invalid(
+                "language must name a string table under assets/locales/".to_string(),
+            ));
+        }
+
+        self.gameplay
+            .validate()
+            .and_then(|_| self.video.validate())
+            .and_then(|_| self.audio.validate())
+            .map_err(SettingsError::Invalid)
+    }
+}
+
+struct SettingsLoadError(Option<SettingsError>);
+
+/// The player's own gameplay settings, saved in place of `Settings::gameplay`
+/// while a dev config overrides it.
+pub struct SavedGameplay(pub GameplaySettings);
+
+fn report_load_error(error: Res<SettingsLoadError>) {
+    if let Some(error) = &error.0 {
+        error!("{}; using default settings", error);
+    }
+}
+
+fn save_settings(settings: Res<Settings>, saved: Option<Res<SavedGameplay>>) {
+    if settings.is_changed() && !settings.is_added() {
+        let result = match saved {
+            Some(saved) => Settings {
+                gameplay: saved.0.clone(),
+                ..settings.clone()
+            }
+            .save(),
+            None => settings.save(),
+        };
+
+        if let Err(error) = result {
+            error!("{}", error);
+        }
+    }
+}
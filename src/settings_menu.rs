@@ -0,0 +1,301 @@
+use bevy::prelude::*;
+
+use crate::game_state::{CleanUp, GameState};
+use crate::locale::{Message, LANGUAGES};
+use crate::primitives::menu::{Menu, MenuEvent, MenuPlugin, MenuSystem};
+use crate::settings::{ArenaPattern, CameraMode, PaletteMode, Settings};
+use crate::theme::THEMES;
+
+pub struct SettingsMenuPlugin;
+
+impl Plugin for SettingsMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugin(MenuPlugin::<SettingsMenuItem>::default())
+            .add_system_set(SystemSet::on_enter(GameState::SettingsMenu).with_system(spawn_ui))
+            .add_system_set(
+                SystemSet::on_update(GameState::SettingsMenu)
+                    .with_system(change_settings.after(MenuSystem::Navigate))
+                    .with_system(update_labels.after(MenuSystem::Navigate)),
+            );
+    }
+}
+
+const VISIBLE_ROWS: usize = 9;
+
+const ITEMS: [SettingsMenuItem; 20] = [
+    SettingsMenuItem::Speed,
+    SettingsMenuItem::ArenaWidth,
+    SettingsMenuItem::ArenaHeight,
+    SettingsMenuItem::Ghost,
+    SettingsMenuItem::Letterbox,
+    SettingsMenuItem::SmoothMovement,
+    SettingsMenuItem::Particles,
+    SettingsMenuItem::Camera,
+    SettingsMenuItem::ScreenShake,
+    SettingsMenuItem::ArenaPattern,
+    SettingsMenuItem::Palette,
+    SettingsMenuItem::ShapeMarkers,
+    SettingsMenuItem::Theme,
+    SettingsMenuItem::Language,
+    SettingsMenuItem::MasterVolume,
+    SettingsMenuItem::MusicVolume,
+    SettingsMenuItem::SfxVolume,
+    SettingsMenuItem::Mute,
+    SettingsMenuItem::AudioCues,
+    SettingsMenuItem::Back,
+];
+
+const PATTERNS: [ArenaPattern; 3] = [
+    ArenaPattern::Plain,
+    ArenaPattern::Grid,
+    ArenaPattern::Checkerboard,
+];
+
+const PALETTES: [PaletteMode; 5] = [
+    PaletteMode::Theme,
+    PaletteMode::Deuteranopia,
+    PaletteMode::Protanopia,
+    PaletteMode::Tritanopia,
+    PaletteMode::HighContrast,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SettingsMenuItem {
+    Speed,
+    ArenaWidth,
+    ArenaHeight,
+    Ghost,
+    Letterbox,
+    SmoothMovement,
+    Particles,
+    Camera,
+    ScreenShake,
+    ArenaPattern,
+    Palette,
+    ShapeMarkers,
+    Theme,
+    Language,
+    MasterVolume,
+    MusicVolume,
+    SfxVolume,
+    Mute,
+    AudioCues,
+    Back,
+}
+
+impl SettingsMenuItem {
+    fn label(self, settings: &Settings) -> Message {
+        let on_off = |value: bool| Message::new(if value { "common.on" } else { "common.off" });
+        let percent = |value: f32| (value * 100.0).round() as i32;
+
+        match self {
+            Self::Speed => Message::new("settings.speed").arg("ms", settings.gameplay.tick_millis),
+            Self::ArenaWidth => {
+                Message::new("settings.arena_width").arg("width", settings.gameplay.arena_width)
+            }
+            Self::ArenaHeight => {
+                Message::new("settings.arena_height").arg("height", settings.gameplay.arena_height)
+            }
+            Self::Ghost => {
+                Message::new("settings.ghost").arg_message("value", on_off(settings.gameplay.ghost))
+            }
+            Self::Letterbox => Message::new("settings.letterbox")
+                .arg_message("value", on_off(settings.video.letterbox)),
+            Self::SmoothMovement => Message::new("settings.smooth_movement")
+                .arg_message("value", on_off(settings.video.smooth_movement)),
+            Self::Particles => Message::new("settings.particles")
+                .arg_message("value", on_off(settings.video.particles)),
+            Self::Camera => {
+                let mode = match settings.video.camera {
+                    CameraMode::Fit => "camera.fit",
+                    CameraMode::Follow => "camera.follow",
+                };
+                Message::new("settings.camera").arg_message("mode", mode.into())
+            }
+            Self::ScreenShake => Message::new("settings.screen_shake")
+                .arg_message("value", on_off(settings.video.screen_shake)),
+            Self::ArenaPattern => {
+                let pattern = match settings.video.arena_pattern {
+                    ArenaPattern::Plain => "pattern.plain",
+                    ArenaPattern::Grid => "pattern.grid",
+                    ArenaPattern::Checkerboard => "pattern.checkerboard",
+                };
+                Message::new("settings.arena_pattern").arg_message("pattern", pattern.into())
+            }
+            Self::Palette => {
+                let palette = match settings.video.palette {
+                    PaletteMode::Theme => "palette.theme",
+                    PaletteMode::Deuteranopia => "palette.deuteranopia",
+                    PaletteMode::Protanopia => "palette.protanopia",
+                    PaletteMode::Tritanopia => "palette.tritanopia",
+                    PaletteMode::HighContrast => "palette.high_contrast",
+                };
+                Message::new("settings.palette").arg_message("palette", palette.into())
+            }
+            Self::ShapeMarkers => Message::new("settings.shape_markers")
+                .arg_message("value", on_off(settings.video.shape_markers)),
+            Self::Theme => Message::new("settings.theme").arg("theme", &settings.video.theme),
+            Self::Language => {
+                let name = LANGUAGES
+                    .iter()
+                    .find(|(code, _)| *code == settings.language)
+                    .map_or(settings.language.as_str(), |(_, name)| name);
+                Message::new("settings.language").arg("language", name)
+            }
+            Self::MasterVolume => Message::new("settings.master_volume")
+                .arg("percent", percent(settings.audio.master_volume)),
+            Self::MusicVolume => Message::new("settings.music_volume")
+                .arg("percent", percent(settings.audio.music_volume)),
+            Self::SfxVolume => Message::new("settings.sfx_volume")
+                .arg("percent", percent(settings.audio.sfx_volume)),
+            Self::Mute => {
+                Message::new("settings.mute").arg_message("value", on_off(settings.audio.muted))
+            }
+            Self::AudioCues => Message::new("settings.audio_cues")
+                .arg_message("value", on_off(settings.audio.audio_cues)),
+            Self::Back => Message::new("common.back"),
+        }
+    }
+
+    fn adjust(self, settings: &mut Settings, step: i32) {
+        let volume = |value: &mut f32| {
+            *value = (*value + step as f32 * 0.1).clamp(0.0, 1.0);
+        };
+
+        match self {
+            Self::Speed => {
+                let tick = settings.gameplay.tick_millis as i64 + step as i64 * 10;
+                settings.gameplay.tick_millis = tick.clamp(10, 2000) as u64;
+            }
+            Self::ArenaWidth => {
+                settings.gameplay.arena_width =
+                    (settings.gameplay.arena_width + step * 2).clamp(5, 101)
+            }
+            Self::ArenaHeight => {
+                settings.gameplay.arena_height =
+                    (settings.gameplay.arena_height + step * 2).clamp(5, 101)
+            }
+            Self::Ghost => settings.gameplay.ghost = !settings.gameplay.ghost,
+            Self::Letterbox => settings.video.letterbox = !settings.video.letterbox,
+            Self::SmoothMovement => {
+                settings.video.smooth_movement = !settings.video.smooth_movement
+            }
+            Self::Particles => settings.video.particles = !settings.video.particles,
+            Self::Camera => {
+                settings.video.camera = match settings.video.camera {
+                    CameraMode::Fit => CameraMode::Follow,
+                    CameraMode::Follow => CameraMode::Fit,
+                }
+            }
+            Self::ScreenShake => settings.video.screen_shake = !settings.video.screen_shake,
+            Self::ArenaPattern => {
+                let current = PATTERNS
+                    .iter()
+                    .position(|pattern| *pattern == settings.video.arena_pattern)
+                    .unwrap_or(0) as i32;
+                let next = (current + step).rem_euclid(PATTERNS.len() as i32);
+                settings.video.arena_pattern = PATTERNS[next as usize];
+            }
+            Self::Palette => {
+                let current = PALETTES
+                    .iter()
+                    .position(|palette| *palette == settings.video.palette)
+                    .unwrap_or(0) as i32;
+                let next = (current + step).rem_euclid(PALETTES.len() as i32);
+                settings.video.palette = PALETTES[next as usize];
+            }
+            Self::ShapeMarkers => settings.video.shape_markers = !settings.video.shape_markers,
+            Self::Theme => {
+                let current = THEMES
+                    .iter()
+                    .position(|theme| *theme == settings.video.theme)
+                    .unwrap_or(0) as i32;
+                let next = (current + step).rem_euclid(THEMES.len() as i32);
+                settings.video.theme = THEMES[next as usize].to_string();
+            }
+            Self::Language => {
+                let current = LANGUAGES
+                    .iter()
+                    .position(|(code, _)| *code == settings.language)
+                    .unwrap_or(0) as i32;
+                let next = (current + step).rem_euclid(LANGUAGES.len() as i32);
+                settings.language = LANGUAGES[next as usize].0.to_string();
+            }
+            Self::MasterVolume => volume(&mut settings.audio.master_volume),
+            Self::MusicVolume => volume(&mut settings.audio.music_volume),
+            Self::SfxVolume => volume(&mut settings.audio.sfx_volume),
+            Self::Mute => settings.audio.muted = !settings.audio.muted,
+            Self::AudioCues => settings.audio.audio_cues = !settings.audio.audio_cues,
+            Self::Back => {}
+        }
+    }
+}
+
+fn spawn_ui(mut commands: Commands, settings: Res<Settings>) {
+    let menu = ITEMS
+        .iter()
+        .fold(Menu::new("settings.title"), |menu, item| {
+            menu.item(item.label(&settings), *item)
+        })
+        .on_back(SettingsMenuItem::Back)
+        .with_overlay()
+        .with_rows(VISIBLE_ROWS);
+
+    commands
+        .spawn()
+        .insert(menu)
+        .insert(CleanUp::new(GameState::SettingsMenu));
+}
+
+fn change_settings(
+    mut events: EventReader<MenuEvent<SettingsMenuItem>>,
+    mut state: ResMut<State<GameState>>,
+    mut settings: ResMut<Settings>,
+) {
+    let mut back = false;
+
+    for event in events.iter() {
+        match *event {
+            MenuEvent::Adjust(item, step) => item.adjust(&mut settings, step),
+            MenuEvent::Confirm(
+                item @ (SettingsMenuItem::Ghost
+                | SettingsMenuItem::Letterbox
+                | SettingsMenuItem::SmoothMovement
+                | SettingsMenuItem::Particles
+                | SettingsMenuItem::Camera
+                | SettingsMenuItem::ScreenShake
+                | SettingsMenuItem::ArenaPattern
+                | SettingsMenuItem::Palette
+                | SettingsMenuItem::ShapeMarkers
+                | SettingsMenuItem::Mute
+                | SettingsMenuItem::AudioCues),
+            ) => item.adjust(&mut settings, 1),
+            MenuEvent::Confirm(SettingsMenuItem::Back) => back = true,
+            MenuEvent::Confirm(_) => {}
+        }
+    }
+
+    if back {
+        if state.inactives().is_empty() {
+            state.replace(GameState::StartMenu).unwrap();
+        } else {
+            state.pop().unwrap();
+        }
+    }
+}
+
+fn update_labels(settings: Res<Settings>, mut menus: Query<&mut Menu<SettingsMenuItem>>) {
+    if settings.is_changed() {
+        for mut menu in menus.iter_mut() {
+            let stale = menu
+                .items
+                .iter()
+                .any(|item| item.label != item.action.label(&settings));
+            if stale {
+                for item in menu.items.iter_mut() {
+                    item.label = item.action.label(&settings);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,144 @@
+use bevy::prelude::*;
+
+use crate::game_loop::{GameStage, SnakeHead};
+use crate::game_state::GameState;
+use crate::primitives::{Direction, Position};
+
+const SNAKE_ATLAS: &str = "textures/snake.png";
+const TILE_SIZE: f32 = 32.0;
+
+pub struct SnakeSpritesPlugin;
+
+impl Plugin for SnakeSpritesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SnakeAtlas>().add_system_set(
+            SystemSet::on_update(GameState::MainGameLoop)
+                .with_system(orient_segments.after(GameStage::Movement)),
+        );
+    }
+}
+
+/// Tiles of the snake atlas, in the order they appear in the texture. Every
+/// tile is drawn facing right; `orient_segments` rotates it into place.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SegmentSprite {
+    /// Facing towards +x.
+    Head,
+    /// Joining -x and +x.
+    Straight,
+    /// Joining +x and +y.
+    Corner,
+    /// Joining +x, tapering off towards -x.
+    TailTip,
+}
+
+impl SegmentSprite {
+    const COUNT: usize = 4;
+
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    /// Picks the tile and rotation for a segment from the cells of its
+    /// neighbours towards the head (`ahead`) and away from it (`behind`).
+    fn for_segment(
+        position: Position,
+        ahead: Option<Position>,
+        behind: Option<Position>,
+        heading: Direction,
+    ) -> (Self, f32) {
+        let ahead = ahead.and_then(|ahead| Direction::between(position, ahead));
+        let behind = behind.and_then(|behind| Direction::between(position, behind));
+
+        match (ahead, behind) {
+            (None, None) => (Self::Straight, heading.angle()),
+            (Some(ahead), None) => (Self::TailTip, ahead.angle()),
+            (None, Some(behind)) => (Self::Straight, behind.angle()),
+            (Some(ahead), Some(behind)) if ahead == behind.opposite() => {
+                (Self::Straight, ahead.angle())
+            }
+            (Some(ahead), Some(behind)) => {
+                let first = if behind == ahead.counter_clockwise() {
+                    ahead
+                } else {
+                    behind
+                };
+                (Self::Corner, first.angle())
+            }
+        }
+    }
+}
+
+pub struct SnakeAtlas(Handle<TextureAtlas>);
+
+impl SnakeAtlas {
+    pub fn bundle(&self, sprite: SegmentSprite, color: Color) -> SpriteSheetBundle {
+        SpriteSheetBundle {
+            sprite: TextureAtlasSprite {
+                index: sprite.index(),
+                color,
+                custom_size: Some(Vec2::ONE),
+                ..Default::default()
+            },
+            texture_atlas: self.0.clone(),
+            transform: Transform {
+                translation: Vec3::new(0.0, 0.0, 1.0),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+}
+
+impl FromWorld for SnakeAtlas {
+    fn from_world(world: &mut World) -> Self {
+        let texture = world
+            .get_resource::<AssetServer>()
+            .unwrap()
+            .load(SNAKE_ATLAS);
+        let atlas =
+            TextureAtlas::from_grid(texture, Vec2::splat(TILE_SIZE), SegmentSprite::COUNT, 1);
+
+        let mut atlases = world.get_resource_mut::<Assets<TextureAtlas>>().unwrap();
+        SnakeAtlas(atlases.add(atlas))
+    }
+}
+
+fn orient_segments(
+    heads: Query<(Entity, &SnakeHead)>,
+    positions: Query<&Position>,
+    mut segments: Query<(&mut TextureAtlasSprite, &mut Transform)>,
+) {
+    for (snake, head) in heads.iter() {
+        let body: Vec<_> = Some(snake)
+            .into_iter()
+            .chain(head.tail.iter().rev().copied())
+            .filter_map(|entity| Some((entity, *positions.get(entity).ok()?)))
+            .collect();
+
+        for (index, (entity, position)) in body.iter().enumerate() {
+            let (sprite, angle) = if index == 0 {
+                (SegmentSprite::Head, head.body.direction.angle())
+            } else {
+                SegmentSprite::for_segment(
+                    *position,
+                    Some(body[index - 1].1),
+                    body.get(index + 1).map(|(_, position)| *position),
+                    head.body.direction,
+                )
+            };
+
+            let (mut atlas_sprite, mut transform) = match segments.get_mut(*entity) {
+                Ok(segment) => segment,
+                Err(_) => continue,
+            };
+            if atlas_sprite.index != sprite.index() {
+                atlas_sprite.index = sprite.index();
+            }
+            let rotation = Quat::from_rotation_z(angle);
+            if transform.rotation != rotation {
+                transform.rotation = rotation;
+            }
+        }
+    }
+}
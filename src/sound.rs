@@ -0,0 +1,734 @@
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::io::Cursor;
+use std::iter;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use bevy::asset::{AssetServerSettings, FileAssetIo};
+use bevy::prelude::*;
+use rodio::source::ChannelVolume;
+use rodio::{Decoder, OutputStream, OutputStreamHandle, Sink, Source};
+
+use crate::experience::LevelUpEvent;
+use crate::game_loop::{CollectEvent, SnakeHead, TickEvent};
+use crate::game_state::GameState;
+use crate::primitives::menu::MenuCue;
+use crate::primitives::Direction;
+use crate::settings::Settings;
+
+/// Set to `null` to run without opening an audio device.
+const BACKEND_VAR: &str = "SNAKE_AUDIO";
+
+/// Snake length, counting the head, at which each layer of the game track
+/// fades in.
+const LAYER_LENGTHS: [usize; 2] = [6, 16];
+/// Fraction of a layer's full volume faded in or out per second.
+const LAYER_FADE: f32 = 0.5;
+/// Tick length the game track was written for; faster ticks play it faster.
+const REFERENCE_TICK: f32 = 0.1;
+const MIN_SPEED: f32 = 0.75;
+const MAX_SPEED: f32 = 1.5;
+
+pub struct SoundPlugin;
+
+impl Plugin for SoundPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SoundOutput>()
+            .init_resource::<MusicMix>()
+            .add_system(toggle_mute)
+            .add_system(play_state_music.label(SoundSystem::Track))
+            .add_system(apply_music_volume.after(SoundSystem::Track))
+            .add_system(apply_music_mix.after(SoundSystem::Track))
+            .add_system(play_menu_sounds)
+            .add_system(play_level_up)
+            .add_system_set(
+                SystemSet::on_update(GameState::MainGameLoop)
+                    .with_system(play_eat_sounds)
+                    .with_system(play_turn_sounds)
+                    .with_system(adapt_music.before(SoundSystem::Track)),
+            )
+            .add_system_set(SystemSet::on_enter(GameState::Dying).with_system(play_death_sound));
+    }
+}
+
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, SystemLabel)]
+enum SoundSystem {
+    Track,
+}
+
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum Sound {
+    Eat,
+    Turn,
+    Die,
+    MenuMove,
+    MenuSelect,
+    LevelUp,
+    /// Musical flourish on level-up, mixed with the music rather than effects.
+    Stinger,
+    FoodCue,
+    DangerCue,
+}
+
+impl Sound {
+    const ALL: [Sound; 9] = [
+        Sound::Eat,
+        Sound::Turn,
+        Sound::Die,
+        Sound::MenuMove,
+        Sound::MenuSelect,
+        Sound::LevelUp,
+        Sound::Stinger,
+        Sound::FoodCue,
+        Sound::DangerCue,
+    ];
+
+    fn path(self) -> &'static str {
+        match self {
+            Sound::Eat => "sounds/eat.wav",
+            Sound::Turn => "sounds/turn.wav",
+            Sound::Die => "sounds/die.wav",
+            Sound::MenuMove => "sounds/menu_move.wav",
+            Sound::MenuSelect => "sounds/menu_select.wav",
+            Sound::LevelUp => "sounds/level_up.wav",
+            Sound::Stinger => "music/stinger.wav",
+            Sound::FoodCue => "sounds/cue_food.wav",
+            Sound::DangerCue => "sounds/cue_danger.wav",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum Music {
+    Menu,
+    Game,
+    GameOver,
+}
+
+impl Music {
+    const ALL: [Music; 3] = [Music::Menu, Music::Game, Music::GameOver];
+
+    fn path(self) -> &'static str {
+        match self {
+            Music::Menu => "music/menu.wav",
+            Music::Game => "music/game.wav",
+            Music::GameOver => "music/game_over.wav",
+        }
+    }
+
+    /// Extra parts that loop in sync with the track, silent until faded in.
+    fn layers(self) -> &'static [&'static str] {
+        match self {
+            Music::Game => &["music/game_drums.wav", "music/game_lead.wav"],
+            Music::Menu | Music::GameOver => &[],
+        }
+    }
+
+    fn for_state(state: GameState) -> Option<Music> {
+        match state {
+            GameState::StartMenu
+            | GameState::ModeMenu
+            | GameState::SettingsMenu
+            | GameState::HighScoreMenu => Some(Music::Menu),
+            GameState::MainGameLoop
+            | GameState::Countdown
+            | GameState::PauseMenu
+            | GameState::ReplayView => Some(Music::Game),
+            GameState::Dying | GameState::GameOver => Some(Music::GameOver),
+        }
+    }
+}
+
+/// Where sounds and music end up. Volumes are final gains in `0.0..=1.0`.
+pub trait SoundBackend: Send + Sync {
+    fn play_sound(&self, sound: Sound, volume: f32);
+    /// Plays `sound` panned between `-1.0` (left) and `1.0` (right), at
+    /// `pitch` times the pitch it was recorded at.
+    fn play_cue(&self, sound: Sound, volume: f32, pan: f32, pitch: f32);
+    /// Starts `music` looping, replacing whatever was playing.
+    fn play_music(&self, music: Music, volume: f32);
+    fn set_music_volume(&self, volume: f32);
+    /// Scales each of the current track's layers, in `Music::layers` order.
+    fn set_music_layers(&self, gains: &[f32]);
+    /// Plays the current track faster or slower; `1.0` is as recorded. Pitch
+    /// follows the speed, like a tape played faster.
+    fn set_music_speed(&self, speed: f32);
+    fn stop_music(&self);
+}
+
+/// Discards everything, for headless runs and machines without audio.
+pub struct NullBackend;
+
+impl SoundBackend for NullBackend {
+    fn play_sound(&self, _sound: Sound, _volume: f32) {}
+    fn play_cue(&self, _sound: Sound, _volume: f32, _pan: f32, _pitch: f32) {}
+    fn play_music(&self, _music: Music, _volume: f32) {}
+    fn set_music_volume(&self, _volume: f32) {}
+    fn set_music_layers(&self, _gains: &[f32]) {}
+    fn set_music_speed(&self, _speed: f32) {}
+    fn stop_music(&self) {}
+}
+
+enum Command {
+    Sound(Sound, f32),
+    Cue(Sound, f32, f32, f32),
+    Music(Music, f32),
+    MusicVolume(f32),
+    MusicLayers(Vec<f32>),
+    MusicSpeed(f32),
+    StopMusic,
+}
+
+/// Samples, per channel, between checks for a new tempo.
+const TEMPO_CHUNK: usize = 1024;
+
+/// Plays `input` at whatever speed is in `speed`, picking up changes every
+/// `TEMPO_CHUNK` samples. rodio's own `Speed` is fixed once it is built.
+/// Like `Speed`, it changes the sample rate, so the music climbs in pitch as
+/// the snake speeds up; that rising whine is part of the effect.
+struct Tempo<I> {
+    input: I,
+    speed: Arc<Mutex<f32>>,
+    factor: f32,
+    remaining: usize,
+}
+
+impl<I: Source<Item = i16>> Tempo<I> {
+    fn new(input: I, speed: Arc<Mutex<f32>>) -> Self {
+        let mut tempo = Tempo {
+            input,
+            speed,
+            factor: 1.0,
+            remaining: 0,
+        };
+        tempo.refresh();
+        tempo
+    }
+
+    fn refresh(&mut self) {
+        self.factor = self.speed.lock().map_or(1.0, |speed| *speed);
+        self.remaining = TEMPO_CHUNK * usize::from(self.input.channels().max(1));
+    }
+}
+
+impl<I: Source<Item = i16>> Iterator for Tempo<I> {
+    type Item = i16;
+
+    fn next(&mut self) -> Option<i16> {
+        let sample = self.input.next()?;
+        self.remaining -= 1;
+        if self.remaining == 0 {
+            self.refresh();
+        }
+        Some(sample)
+    }
+}
+
+impl<I: Source<Item = i16>> Source for Tempo<I> {
+    fn current_frame_len(&self) -> Option<usize> {
+        let remaining = Some(self.remaining);
+        self.input.current_frame_len().min(remaining).or(remaining)
+    }
+
+    fn channels(&self) -> u16 {
+        self.input.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        (self.input.sample_rate() as f32 * self.factor).max(1.0) as u32
+    }
+
+    fn total_duration(&self) -> Option<std::time::Duration> {
+        None
+    }
+}
+
+/// A track and its layers, each on a sink of its own so they can be mixed.
+struct Track {
+    sinks: Vec<Sink>,
+    volume: f32,
+    layers: Vec<f32>,
+    speed: Arc<Mutex<f32>>,
+}
+
+impl Track {
+    fn start(
+        handle: &OutputStreamHandle,
+        sources: Vec<Decoder<Cursor<Arc<[u8]>>>>,
+        volume: f32,
+    ) -> Option<Track> {
+        let speed = Arc::new(Mutex::new(1.0));
+        let sinks = sources
+            .into_iter()
+            .map(|source| {
+                let sink = Sink::try_new(handle).ok()?;
+                sink.pause();
+                sink.append(Tempo::new(source.repeat_infinite(), speed.clone()));
+                Some(sink)
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        let track = Track {
+            sinks,
+            volume,
+            layers: Vec::new(),
+            speed,
+        };
+        track.mix();
+        for sink in &track.sinks {
+            sink.play();
+        }
+        Some(track)
+    }
+
+    fn mix(&self) {
+        for (index, sink) in self.sinks.iter().enumerate() {
+            let gain = match index {
+                0 => 1.0,
+                layer => self.layers.get(layer - 1).copied().unwrap_or(0.0),
+            };
+            sink.set_volume(self.volume * gain);
+        }
+    }
+}
+
+/// Plays through rodio on a thread of its own, since rodio's output stream
+/// can't be shared between the systems that want to make noise.
+pub struct RodioBackend {
+    commands: Mutex<Sender<Command>>,
+}
+
+impl RodioBackend {
+    /// Starts the audio thread, reading sounds from the `root` asset folder.
+    pub fn spawn(root: PathBuf) -> Option<Self> {
+        let (commands, receiver) = mpsc::channel();
+        let (ready, started) = mpsc::channel();
+
+        thread::spawn(move || {
+            let (_stream, handle) = match OutputStream::try_default() {
+                Ok(output) => {
+                    let _ = ready.send(true);
+                    output
+                }
+                Err(error) => {
+                    warn!("could not open an audio device: {}", error);
+                    let _ = ready.send(false);
+                    return;
+                }
+            };
+
+            let sounds = load_all(&root, Sound::ALL.iter().map(|sound| (*sound, sound.path())));
+            let tracks = load_all(
+                &root,
+                Music::ALL
+                    .iter()
+                    .flat_map(|music| {
+                        iter::once(music.path()).chain(music.layers().iter().copied())
+                    })
+                    .map(|path| (path, path)),
+            );
+            let mut music: Option<Track> = None;
+
+            for command in receiver {
+                match command {
+                    Command::Sound(sound, volume) => {
+                        if let Some(source) = sounds.get(&sound).and_then(decode) {
+                            let _ = handle.play_raw(source.amplify(volume).convert_samples());
+                        }
+                    }
+                    Command::Cue(sound, volume, pan, pitch) => {
+                        if let Some(source) = sounds.get(&sound).and_then(decode) {
+                            let left = ((1.0 - pan) / 2.0).sqrt() * volume;
+                            let right = ((1.0 + pan) / 2.0).sqrt() * volume;
+                            let source = source.speed(pitch).convert_samples::<f32>();
+                            let _ = handle.play_raw(ChannelVolume::new(source, vec![left, right]));
+                        }
+                    }
+                    Command::Music(track, volume) => {
+                        music = iter::once(track.path())
+                            .chain(track.layers().iter().copied())
+                            .map(|path| tracks.get(path).and_then(decode))
+                            .collect::<Option<Vec<_>>>()
+                            .and_then(|sources| Track::start(&handle, sources, volume));
+                    }
+                    Command::MusicVolume(volume) => {
+                        if let Some(track) = &mut music {
+                            track.volume = volume;
+                            track.mix();
+                        }
+                    }
+                    Command::MusicLayers(gains) => {
+                        if let Some(track) = &mut music {
+                            track.layers = gains;
+                            track.mix();
+                        }
+                    }
+                    Command::MusicSpeed(speed) => {
+                        if let Some(track) = &music {
+                            if let Ok(mut current) = track.speed.lock() {
+                                *current = speed;
+                            }
+                        }
+                    }
+                    Command::StopMusic => music = None,
+                }
+            }
+        });
+
+        match started.recv() {
+            Ok(true) => Some(RodioBackend {
+                commands: Mutex::new(commands),
+            }),
+            _ => None,
+        }
+    }
+
+    fn send(&self, command: Command) {
+        if let Ok(commands) = self.commands.lock() {
+            let _ = commands.send(command);
+        }
+    }
+}
+
+impl SoundBackend for RodioBackend {
+    fn play_sound(&self, sound: Sound, volume: f32) {
+        self.send(Command::Sound(sound, volume));
+    }
+
+    fn play_cue(&self, sound: Sound, volume: f32, pan: f32, pitch: f32) {
+        self.send(Command::Cue(sound, volume, pan, pitch));
+    }
+
+    fn play_music(&self, music: Music, volume: f32) {
+        self.send(Command::Music(music, volume));
+    }
+
+    fn set_music_volume(&self, volume: f32) {
+        self.send(Command::MusicVolume(volume));
+    }
+
+    fn set_music_layers(&self, gains: &[f32]) {
+        self.send(Command::MusicLayers(gains.to_vec()));
+    }
+
+    fn set_music_speed(&self, speed: f32) {
+        self.send(Command::MusicSpeed(speed));
+    }
+
+    fn stop_music(&self) {
+        self.send(Command::StopMusic);
+    }
+}
+
+fn load_all<K: std::hash::Hash + Eq>(
+    root: &Path,
+    paths: impl Iterator<Item = (K, &'static str)>,
+) -> HashMap<K, Arc<[u8]>> {
+    paths
+        .filter_map(|(key, path)| match fs::read(root.join(path)) {
+            Ok(bytes) => Some((key, Arc::from(bytes))),
+            Err(error) => {
+                warn!("could not load {}: {}", path, error);
+                None
+            }
+        })
+        .collect()
+}
+
+fn decode(bytes: &Arc<[u8]>) -> Option<Decoder<Cursor<Arc<[u8]>>>> {
+    Decoder::new(Cursor::new(bytes.clone())).ok()
+}
+
+pub struct SoundOutput(Box<dyn SoundBackend>);
+
+impl SoundOutput {
+    pub fn new(backend: impl SoundBackend + 'static) -> Self {
+        SoundOutput(Box::new(backend))
+    }
+
+    pub fn play(&self, settings: &Settings, sound: Sound) {
+        let volume = settings.audio.sfx_gain();
+        if volume > 0.0 {
+            self.0.play_sound(sound, volume);
+        }
+    }
+
+    pub fn play_cue(&self, settings: &Settings, sound: Sound, volume: f32, pan: f32, pitch: f32) {
+        let volume = settings.audio.sfx_gain() * volume;
+        if volume > 0.0 {
+            self.0.play_cue(sound, volume, pan.clamp(-1.0, 1.0), pitch);
+        }
+    }
+}
+
+/// How the playing track's layers and tempo are set, reset with every new track.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct MusicMix {
+    layers: [f32; LAYER_LENGTHS.len()],
+    speed: f32,
+}
+
+impl Default for MusicMix {
+    fn default() -> Self {
+        MusicMix {
+            layers: [0.0; LAYER_LENGTHS.len()],
+            speed: 1.0,
+        }
+    }
+}
+
+impl FromWorld for SoundOutput {
+    fn from_world(world: &mut World) -> Self {
+        if env::var(BACKEND_VAR).is_ok_and(|backend| backend == "null") {
+            return SoundOutput::new(NullBackend);
+        }
+
+        // Sounds come from the same folder the AssetServer loads from.
+        let folder = world.get_resource::<AssetServerSettings>().map_or_else(
+            || AssetServerSettings::default().asset_folder,
+            |settings| settings.asset_folder.clone(),
+        );
+        match RodioBackend::spawn(FileAssetIo::get_root_path().join(folder)) {
+            Some(backend) => SoundOutput::new(backend),
+            None => SoundOutput::new(NullBackend),
+        }
+    }
+}
+
+fn toggle_mute(mut keys: ResMut<Input<KeyCode>>, mut settings: ResMut<Settings>) {
+    if keys.just_pressed(KeyCode::M) {
+        keys.reset(KeyCode::M);
+        settings.audio.muted = !settings.audio.muted;
+    }
+}
+
+fn play_state_music(
+    state: Res<State<GameState>>,
+    settings: Res<Settings>,
+    output: Res<SoundOutput>,
+    mut mix: ResMut<MusicMix>,
+    mut playing: Local<Option<Music>>,
+) {
+    if !state.is_changed() {
+        return;
+    }
+
+    let music = Music::for_state(*state.current());
+    if *playing == music {
+        return;
+    }
+
+    match music {
+        Some(music) => output.0.play_music(music, settings.audio.music_gain()),
+        None => output.0.stop_music(),
+    }
+    *mix = MusicMix::default();
+    *playing = music;
+}
+
+fn apply_music_volume(settings: Res<Settings>, output: Res<SoundOutput>) {
+    if settings.is_changed() {
+        output.0.set_music_volume(settings.audio.music_gain());
+    }
+}
+
+fn adapt_music(time: Res<Time>, heads: Query<(&SnakeHead, &Timer)>, mut mix: ResMut<MusicMix>) {
+    let (head, timer) = match heads.iter().next() {
+        Some(head) => head,
+        None => return,
+    };
+
+    let step = LAYER_FADE * time.delta_seconds();
+    let mut next = *mix;
+    for (gain, length) in next.layers.iter_mut().zip(LAYER_LENGTHS) {
+        let target = if head.body.length + 1 >= length {
+            1.0
+        } else {
+            0.0
+        };
+        *gain += (target - *gain).clamp(-step, step);
+    }
+    next.speed = (REFERENCE_TICK / timer.duration().as_secs_f32()).clamp(MIN_SPEED, MAX_SPEED);
+
+    if *mix != next {
+        *mix = next;
+    }
+}
+
+fn apply_music_mix(mix: Res<MusicMix>, output: Res<SoundOutput>) {
+    if mix.is_changed() {
+        output.0.set_music_layers(&mix.layers);
+        output.0.set_music_speed(mix.speed);
+    }
+}
+
+fn play_menu_sounds(
+    settings: Res<Settings>,
+    output: Res<SoundOutput>,
+    mut cues: EventReader<MenuCue>,
+) {
+    for cue in cues.iter() {
+        let sound = match cue {
+            MenuCue::Move => Sound::MenuMove,
+            MenuCue::Select => Sound::MenuSelect,
+        };
+        output.play(&settings, sound);
+    }
+}
+
+fn play_eat_sounds(
+    settings: Res<Settings>,
+    output: Res<SoundOutput>,
+    mut events: EventReader<CollectEvent>,
+) {
+    if events.iter().count() > 0 {
+        output.play(&settings, Sound::Eat);
+    }
+}
+
+/// Each run spawns a new snake, so its first tick is never compared with
+/// where the last run's snake was heading.
+fn play_turn_sounds(
+    settings: Res<Settings>,
+    output: Res<SoundOutput>,
+    mut events: EventReader<TickEvent>,
+    mut heading: Local<Option<(Entity, Direction)>>,
+) {
+    for event in events.iter() {
+        if let Some((snake, heading)) = *heading {
+            if snake == event.snake && heading != event.heading {
+                output.play(&settings, Sound::Turn);
+            }
+        }
+        *heading = Some((event.snake, event.heading));
+    }
+}
+
+fn play_death_sound(settings: Res<Settings>, output: Res<SoundOutput>) {
+    output.play(&settings, Sound::Die);
+}
+
+/// A level-up plays the stinger over the music, or the plain effect when the
+/// music can't be heard.
+fn play_level_up(
+    settings: Res<Settings>,
+    output: Res<SoundOutput>,
+    mut events: EventReader<LevelUpEvent>,
+) {
+    if events.iter().count() == 0 {
+        return;
+    }
+
+    let volume = settings.audio.music_gain();
+    if volume > 0.0 {
+        output.0.play_sound(Sound::Stinger, volume);
+    } else {
+        output.play(&settings, Sound::LevelUp);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitives::Position;
+    use bevy::app::Events;
+
+    /// Remembers every sound it is asked to play.
+    #[derive(Clone, Default)]
+    struct Recorder(Arc<Mutex<Vec<Sound>>>);
+
+    impl Recorder {
+        fn take(&self) -> Vec<Sound> {
+            std::mem::take(&mut *self.0.lock().unwrap())
+        }
+    }
+
+    impl SoundBackend for Recorder {
+        fn play_sound(&self, sound: Sound, _volume: f32) {
+            self.0.lock().unwrap().push(sound);
+        }
+        fn play_cue(&self, sound: Sound, _volume: f32, _pan: f32, _pitch: f32) {
+            self.0.lock().unwrap().push(sound);
+        }
+        fn play_music(&self, _music: Music, _volume: f32) {}
+        fn set_music_volume(&self, _volume: f32) {}
+        fn set_music_layers(&self, _gains: &[f32]) {}
+        fn set_music_speed(&self, _speed: f32) {}
+        fn stop_music(&self) {}
+    }
+
+    fn app(output: SoundOutput) -> App {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .insert_resource(output)
+            .insert_resource(Settings::default())
+            .init_resource::<Input<KeyCode>>()
+            .add_event::<MenuCue>()
+            .add_event::<CollectEvent>()
+            .add_event::<TickEvent>()
+            .add_event::<LevelUpEvent>()
+            .add_state(GameState::MainGameLoop)
+            .add_plugin(SoundPlugin);
+        app
+    }
+
+    fn tick(app: &mut App, snake: Entity, heading: Direction) {
+        app.world
+            .get_resource_mut::<Events<TickEvent>>()
+            .unwrap()
+            .send(TickEvent {
+                snake,
+                heading,
+                position: Position::default(),
+                tail: 0,
+                food: Vec::new(),
+            });
+        app.update();
+    }
+
+    #[test]
+    fn plays_game_sounds_headless() {
+        let recorder = Recorder::default();
+        let mut app = app(SoundOutput::new(recorder.clone()));
+        let snake = app.world.spawn().id();
+
+        tick(&mut app, snake, Direction::Right);
+        tick(&mut app, snake, Direction::Up);
+        app.world
+            .get_resource_mut::<Events<LevelUpEvent>>()
+            .unwrap()
+            .send(LevelUpEvent(2));
+        app.world
+            .get_resource_mut::<State<GameState>>()
+            .unwrap()
+            .set(GameState::Dying)
+            .unwrap();
+        app.update();
+        app.update();
+
+        assert_eq!(
+            recorder.take(),
+            vec![Sound::Turn, Sound::Stinger, Sound::Die]
+        );
+    }
+
+    #[test]
+    fn turn_sounds_start_fresh_with_each_snake() {
+        let recorder = Recorder::default();
+        let mut app = app(SoundOutput::new(recorder.clone()));
+
+        let first = app.world.spawn().id();
+        tick(&mut app, first, Direction::Right);
+        tick(&mut app, first, Direction::Up);
+        assert_eq!(recorder.take(), vec![Sound::Turn]);
+
+        let second = app.world.spawn().id();
+        tick(&mut app, second, Direction::Right);
+        tick(&mut app, second, Direction::Right);
+        assert_eq!(recorder.take(), vec![]);
+    }
+}
@@ -1,41 +1,70 @@
+use bevy::app::AppExit;
 use bevy::prelude::*;
 
-use crate::game_state::{CleanUp, GameState};
+use crate::game_state::{CleanUp, GameMode, GameState};
+use crate::primitives::menu::{confirmed, Menu, MenuEvent, MenuPlugin, MenuSystem};
+use crate::replay::Seed;
 
 pub struct StartMenuPlugin;
 
 impl Plugin for StartMenuPlugin {
     fn build(&self, app: &mut App) {
-        app.add_system_set(SystemSet::on_enter(GameState::StartMenu).with_system(spawn_ui))
-            .add_system_set(SystemSet::on_update(GameState::StartMenu).with_system(start_game));
+        app.add_plugin(MenuPlugin::<StartMenuItem>::default())
+            .add_system_set(
+                SystemSet::on_enter(GameState::StartMenu)
+                    .with_system(spawn_ui)
+                    .with_system(reset_run),
+            )
+            .add_system_set(
+                SystemSet::on_update(GameState::StartMenu)
+                    .with_system(select_item.after(MenuSystem::Navigate)),
+            );
     }
 }
 
-fn spawn_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
-    let font = asset_server.load("fonts/FiraSans-Bold.ttf");
-    let text_style = TextStyle {
-        font,
-        font_size: 60.0,
-        color: Color::WHITE,
-    };
-    let text_alignment = TextAlignment {
-        vertical: VerticalAlign::Center,
-        horizontal: HorizontalAlign::Center,
-    };
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StartMenuItem {
+    Play,
+    Modes,
+    Settings,
+    HighScores,
+    Quit,
+}
+
+fn spawn_ui(mut commands: Commands) {
     commands
-        .spawn_bundle(Text2dBundle {
-            text: Text::with_section("Start", text_style, text_alignment),
-            transform: Transform {
-                translation: Vec3::new(0.0, 0.0, 2.1),
-                ..Default::default()
-            },
-            ..Default::default()
-        })
+        .spawn()
+        .insert(
+            Menu::new("start.title")
+                .item("start.play", StartMenuItem::Play)
+                .item("start.modes", StartMenuItem::Modes)
+                .item("start.settings", StartMenuItem::Settings)
+                .item("start.high_scores", StartMenuItem::HighScores)
+                .item("start.quit", StartMenuItem::Quit),
+        )
         .insert(CleanUp::new(GameState::StartMenu));
 }
 
-fn start_game(input: Res<Input<KeyCode>>, mut state: ResMut<State<GameState>>) {
-    if input.just_pressed(KeyCode::Return) {
-        state.replace(GameState::MainGameLoop).unwrap();
+fn reset_run(mut mode: ResMut<GameMode>, mut seed: ResMut<Seed>) {
+    *mode = GameMode::Classic;
+    *seed = Seed::default();
+}
+
+fn select_item(
+    mut events: EventReader<MenuEvent<StartMenuItem>>,
+    mut state: ResMut<State<GameState>>,
+    mut exit: EventWriter<AppExit>,
+) {
+    let item = match confirmed(&mut events) {
+        Some(item) => item,
+        None => return,
+    };
+
+    match item {
+        StartMenuItem::Play => state.replace(GameState::MainGameLoop).unwrap(),
+        StartMenuItem::Modes => state.replace(GameState::ModeMenu).unwrap(),
+        StartMenuItem::Settings => state.replace(GameState::SettingsMenu).unwrap(),
+        StartMenuItem::HighScores => state.replace(GameState::HighScoreMenu).unwrap(),
+        StartMenuItem::Quit => exit.send(AppExit),
     }
 }
@@ -0,0 +1,368 @@
+use std::fs;
+
+use bevy::asset::{AssetLoader, LoadContext, LoadedAsset};
+use bevy::prelude::*;
+use bevy::reflect::TypeUuid;
+use bevy::utils::BoxedFuture;
+use serde::{Deserialize, Serialize};
+
+use crate::arena::{ARENA_COLOR, GRID_COLOR};
+use crate::game_loop::{EXPERIENCE_BAR_COLOR, FOOD_COLOR, SNAKE_HEAD_COLOR};
+use crate::settings::{PaletteMode, Settings};
+
+pub const THEMES: [&str; 3] = ["default", "midnight", "paper"];
+
+pub struct ThemePlugin;
+
+impl Plugin for ThemePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<Theme>()
+            .init_asset_loader::<ThemeLoader>()
+            .init_resource::<ActiveTheme>()
+            .add_system(switch_theme.label(ThemeSystem::Switch))
+            .add_system(
+                activate_theme
+                    .label(ThemeSystem::Activate)
+                    .after(ThemeSystem::Switch),
+            )
+            .add_system(apply_sprite_colors.after(ThemeSystem::Activate))
+            .add_system(apply_atlas_colors.after(ThemeSystem::Activate))
+            .add_system(apply_ui_colors.after(ThemeSystem::Activate))
+            .add_system(apply_text_styles.after(ThemeSystem::Activate))
+            .add_system(apply_clear_color.after(ThemeSystem::Activate));
+    }
+}
+
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, SystemLabel)]
+pub enum ThemeSystem {
+    Switch,
+    Activate,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TypeUuid)]
+#[uuid = "9d2a6c41-3f8e-4b57-a1d0-6e4c2b8f7a93"]
+#[serde(default)]
+pub struct Theme {
+    pub font: String,
+    pub sizes: FontSizes,
+    pub palette: Palette,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme {
+            font: "fonts/FiraSans-Bold.ttf".to_string(),
+            sizes: FontSizes::default(),
+            palette: Palette::default(),
+        }
+    }
+}
+
+impl Theme {
+    pub fn path(name: &str) -> String {
+        format!("themes/{}.theme.ron", name)
+    }
+
+    pub fn load(name: &str) -> Result<Theme, String> {
+        let path = format!("assets/{}", Theme::path(name));
+        let contents = fs::read_to_string(&path).map_err(|e| format!("{}: {}", path, e))?;
+        ron::from_str(&contents).map_err(|e| format!("{}: {}", path, e))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FontSizes {
+    pub title: f32,
+    pub item: f32,
+    pub body: f32,
+    pub hud: f32,
+    pub countdown: f32,
+}
+
+impl Default for FontSizes {
+    fn default() -> Self {
+        FontSizes {
+            title: 60.0,
+            item: 40.0,
+            body: 30.0,
+            hud: 28.0,
+            countdown: 120.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Palette {
+    pub background: Color,
+    pub snake: Color,
+    pub ghost: Color,
+    pub food: Color,
+    pub arena: Color,
+    pub grid: Color,
+    pub text: Color,
+    pub focus: Color,
+    pub error: Color,
+    pub overlay: Color,
+    pub experience_bar: Color,
+    pub marker: Color,
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Palette {
+            background: Color::rgb(0.4, 0.4, 0.4),
+            snake: SNAKE_HEAD_COLOR,
+            ghost: Color::rgba(0.0, 0.7, 0.7, 0.35),
+            food: FOOD_COLOR,
+            arena: ARENA_COLOR,
+            grid: GRID_COLOR,
+            text: Color::WHITE,
+            focus: Color::rgb(1.0, 0.8, 0.0),
+            error: Color::RED,
+            overlay: Color::rgba(0.0, 0.0, 0.0, 0.5),
+            experience_bar: EXPERIENCE_BAR_COLOR,
+            marker: Color::BLACK,
+        }
+    }
+}
+
+impl Palette {
+    /// The built-in palette that replaces the theme's for `mode`, if any. The
+    /// colour-blind palettes are based on the Okabe-Ito set.
+    pub fn for_mode(mode: PaletteMode) -> Option<Palette> {
+        let palette = match mode {
+            PaletteMode::Theme => return None,
+            PaletteMode::Deuteranopia => Palette {
+                snake: Color::rgb(0.0, 0.45, 0.7),
+                ghost: Color::rgba(0.0, 0.45, 0.7, 0.35),
+                food: Color::rgb(0.9, 0.6, 0.0),
+                arena: Color::rgb(0.85, 0.85, 0.85),
+                grid: Color::rgb(0.75, 0.75, 0.75),
+                focus: Color::rgb(0.94, 0.89, 0.26),
+                error: Color::rgb(0.84, 0.37, 0.0),
+                experience_bar: Color::rgb(0.34, 0.71, 0.91),
+                ..Palette::default()
+            },
+            PaletteMode::Protanopia => Palette {
+                snake: Color::rgb(0.34, 0.71, 0.91),
+                ghost: Color::rgba(0.34, 0.71, 0.91, 0.35),
+                food: Color::rgb(0.94, 0.89, 0.26),
+                arena: Color::rgb(0.3, 0.3, 0.3),
+                grid: Color::rgb(0.38, 0.38, 0.38),
+                background: Color::rgb(0.12, 0.12, 0.12),
+                focus: Color::rgb(0.94, 0.89, 0.26),
+                error: Color::rgb(0.9, 0.6, 0.0),
+                experience_bar: Color::rgb(0.34, 0.71, 0.91),
+                ..Palette::default()
+            },
+            PaletteMode::Tritanopia => Palette {
+                snake: Color::rgb(0.84, 0.37, 0.0),
+                ghost: Color::rgba(0.84, 0.37, 0.0, 0.35),
+                food: Color::rgb(0.0, 0.62, 0.45),
+                arena: Color::rgb(0.85, 0.85, 0.85),
+                grid: Color::rgb(0.75, 0.75, 0.75),
+                focus: Color::rgb(0.8, 0.47, 0.65),
+                error: Color::rgb(0.8, 0.47, 0.65),
+                experience_bar: Color::rgb(0.84, 0.37, 0.0),
+                ..Palette::default()
+            },
+            PaletteMode::HighContrast => Palette {
+                background: Color::BLACK,
+                snake: Color::WHITE,
+                ghost: Color::rgba(1.0, 1.0, 1.0, 0.3),
+                food: Color::rgb(1.0, 1.0, 0.0),
+                arena: Color::rgb(0.08, 0.08, 0.08),
+                grid: Color::rgb(0.35, 0.35, 0.35),
+                text: Color::WHITE,
+                focus: Color::rgb(1.0, 1.0, 0.0),
+                error: Color::rgb(1.0, 0.2, 0.2),
+                overlay: Color::rgba(0.0, 0.0, 0.0, 0.85),
+                experience_bar: Color::WHITE,
+                marker: Color::BLACK,
+            },
+        };
+
+        Some(palette)
+    }
+
+    pub fn color(&self, role: ThemeColor) -> Color {
+        match role {
+            ThemeColor::Snake => self.snake,
+            ThemeColor::Ghost => self.ghost,
+            ThemeColor::Food => self.food,
+            ThemeColor::Arena => self.arena,
+            ThemeColor::Grid => self.grid,
+            ThemeColor::Text => self.text,
+            ThemeColor::Error => self.error,
+            ThemeColor::Overlay => self.overlay,
+            ThemeColor::ExperienceBar => self.experience_bar,
+            ThemeColor::Marker => self.marker,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Component)]
+pub enum ThemeColor {
+    Snake,
+    Ghost,
+    Food,
+    Arena,
+    Grid,
+    Text,
+    Error,
+    Overlay,
+    ExperienceBar,
+    Marker,
+}
+
+pub struct ActiveTheme {
+    pub name: String,
+    pub font: Handle<Font>,
+    pub sizes: FontSizes,
+    pub palette: Palette,
+    theme_palette: Palette,
+    mode: PaletteMode,
+    handle: Handle<Theme>,
+}
+
+impl ActiveTheme {
+    fn apply(&mut self, theme: &Theme, asset_server: &AssetServer) {
+        self.font = asset_server.load(theme.font.as_str());
+        self.sizes = theme.sizes;
+        self.theme_palette = theme.palette;
+        self.palette = Palette::for_mode(self.mode).unwrap_or(theme.palette);
+    }
+}
+
+impl FromWorld for ActiveTheme {
+    fn from_world(world: &mut World) -> Self {
+        let (name, mode) = world
+            .get_resource::<Settings>()
+            .map(|settings| (settings.video.theme.clone(), settings.video.palette))
+            .unwrap_or((String::new(), PaletteMode::Theme));
+        let asset_server = world.get_resource::<AssetServer>().unwrap();
+        let theme = Theme::default();
+
+        ActiveTheme {
+            font: asset_server.load(theme.font.as_str()),
+            handle: asset_server.load(Theme::path(&name).as_str()),
+            sizes: theme.sizes,
+            palette: Palette::for_mode(mode).unwrap_or(theme.palette),
+            theme_palette: theme.palette,
+            mode,
+            name,
+        }
+    }
+}
+
+#[derive(Default)]
+struct ThemeLoader;
+
+impl AssetLoader for ThemeLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+        Box::pin(async move {
+            let theme = ron::de::from_bytes::<Theme>(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(theme));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["theme.ron"]
+    }
+}
+
+fn switch_theme(
+    settings: Res<Settings>,
+    asset_server: Res<AssetServer>,
+    themes: Res<Assets<Theme>>,
+    mut active: ResMut<ActiveTheme>,
+) {
+    if settings.is_changed() && settings.video.theme != active.name {
+        active.name = settings.video.theme.clone();
+        active.handle = asset_server.load(Theme::path(&active.name).as_str());
+
+        if let Some(theme) = themes.get(&active.handle) {
+            active.apply(theme, &asset_server);
+        }
+    }
+
+    if settings.is_changed() && settings.video.palette != active.mode {
+        active.mode = settings.video.palette;
+        active.palette = Palette::for_mode(active.mode).unwrap_or(active.theme_palette);
+    }
+}
+
+fn activate_theme(
+    mut events: EventReader<AssetEvent<Theme>>,
+    themes: Res<Assets<Theme>>,
+    asset_server: Res<AssetServer>,
+    mut active: ResMut<ActiveTheme>,
+) {
+    for event in events.iter() {
+        let loaded = match event {
+            AssetEvent::Created { handle } | AssetEvent::Modified { handle } => handle,
+            AssetEvent::Removed { .. } => continue,
+        };
+
+        if *loaded != active.handle {
+            continue;
+        }
+
+        if let Some(theme) = themes.get(loaded) {
+            active.apply(theme, &asset_server);
+        }
+    }
+}
+
+fn apply_sprite_colors(theme: Res<ActiveTheme>, mut sprites: Query<(&ThemeColor, &mut Sprite)>) {
+    if theme.is_changed() {
+        for (role, mut sprite) in sprites.iter_mut() {
+            sprite.color = theme.palette.color(*role);
+        }
+    }
+}
+
+fn apply_atlas_colors(
+    theme: Res<ActiveTheme>,
+    mut sprites: Query<(&ThemeColor, &mut TextureAtlasSprite)>,
+) {
+    if theme.is_changed() {
+        for (role, mut sprite) in sprites.iter_mut() {
+            sprite.color = theme.palette.color(*role);
+        }
+    }
+}
+
+fn apply_ui_colors(theme: Res<ActiveTheme>, mut nodes: Query<(&ThemeColor, &mut UiColor)>) {
+    if theme.is_changed() {
+        for (role, mut color) in nodes.iter_mut() {
+            color.0 = theme.palette.color(*role);
+        }
+    }
+}
+
+fn apply_text_styles(theme: Res<ActiveTheme>, mut texts: Query<(Option<&ThemeColor>, &mut Text)>) {
+    if theme.is_changed() {
+        for (role, mut text) in texts.iter_mut() {
+            for section in text.sections.iter_mut() {
+                section.style.font = theme.font.clone();
+                if let Some(role) = role {
+                    section.style.color = theme.palette.color(*role);
+                }
+            }
+        }
+    }
+}
+
+fn apply_clear_color(theme: Res<ActiveTheme>, mut clear_color: ResMut<ClearColor>) {
+    if theme.is_changed() {
+        clear_color.0 = theme.palette.background;
+    }
+}